@@ -43,6 +43,29 @@ fn init_submodule(sdl_path: &Path) {
     }
 }
 
+/// The SDL_mixer release compiled by the "bundled" feature, matching the mixer bindings.
+#[cfg(all(feature = "bundled", feature = "mixer"))]
+const SDL2_MIXER_BUNDLED_TAG: &str = "release-2.6.3";
+
+#[cfg(all(feature = "bundled", feature = "mixer"))]
+fn retrieve_sdl2_mixer(out_path: &Path) -> PathBuf {
+    let sdl2_mixer_path = out_path.join("SDL_mixer-src");
+    if !sdl2_mixer_path.join("CMakeLists.txt").exists() {
+        // Remove the leftovers of an interrupted clone
+        let _ = fs::remove_dir_all(&sdl2_mixer_path);
+        let status = Command::new("git")
+            .args(["clone", "--depth", "1", "--branch", SDL2_MIXER_BUNDLED_TAG])
+            .arg("https://github.com/libsdl-org/SDL_mixer")
+            .arg(&sdl2_mixer_path)
+            .status()
+            .expect("Git is needed to retrieve the SDL_mixer source files");
+        if !status.success() {
+            panic!("Failed to retrieve SDL_mixer {}", SDL2_MIXER_BUNDLED_TAG);
+        }
+    }
+    sdl2_mixer_path
+}
+
 #[cfg(feature = "use-pkgconfig")]
 fn pkg_config_print(statik: bool, lib_name: &str) {
     pkg_config::Config::new()
@@ -158,6 +181,50 @@ fn compile_sdl2(sdl2_build_path: &Path, target_os: &str) -> PathBuf {
     cfg.build()
 }
 
+// compile SDL_mixer against the bundled SDL2, with the decoders that need no external library
+#[cfg(all(feature = "bundled", feature = "mixer"))]
+fn compile_sdl2_mixer(
+    sdl2_mixer_build_path: &Path,
+    sdl2_compiled_path: &Path,
+    out_path: &Path,
+) -> PathBuf {
+    let mut cfg = cmake::Config::new(sdl2_mixer_build_path);
+    // Keep the build tree apart from SDL2's, which also lives in OUT_DIR
+    cfg.out_dir(out_path.join("SDL_mixer"));
+    if let Ok(profile) = env::var("SDL2_BUILD_PROFILE") {
+        cfg.profile(&profile);
+        cfg.define("CMAKE_CONFIGURATION_TYPES", &profile);
+    } else {
+        cfg.profile("Release");
+        cfg.define("CMAKE_CONFIGURATION_TYPES", "Release");
+    }
+    if let Ok(toolchain) = env::var("SDL2_TOOLCHAIN") {
+        cfg.define("CMAKE_TOOLCHAIN_FILE", &toolchain);
+    }
+
+    cfg.define("CMAKE_PREFIX_PATH", sdl2_compiled_path);
+    cfg.define("SDL2MIXER_SAMPLES", "OFF");
+    cfg.define("SDL2MIXER_VENDORED", "OFF");
+    // WAV, OGG (stb_vorbis), FLAC (dr_flac), MP3 (minimp3) and MIDI (Timidity) are built in;
+    // the other decoders depend on system libraries.
+    cfg.define("SDL2MIXER_VORBIS", "STB");
+    cfg.define("SDL2MIXER_FLAC_LIBFLAC", "OFF");
+    cfg.define("SDL2MIXER_MP3_MPG123", "OFF");
+    cfg.define("SDL2MIXER_MIDI_FLUIDSYNTH", "OFF");
+    cfg.define("SDL2MIXER_MOD", "OFF");
+    cfg.define("SDL2MIXER_OPUS", "OFF");
+    cfg.define("SDL2MIXER_WAVPACK", "OFF");
+    cfg.define("SDL2MIXER_GME", "OFF");
+
+    if cfg!(feature = "static-link") {
+        cfg.define("BUILD_SHARED_LIBS", "OFF");
+    } else {
+        cfg.define("BUILD_SHARED_LIBS", "ON");
+    }
+
+    cfg.build()
+}
+
 #[cfg(not(feature = "bundled"))]
 fn compute_include_paths(fallback_path: String) -> Vec<String> {
     let mut include_paths: Vec<String> = vec![];
@@ -254,7 +321,15 @@ fn link_sdl2(target_os: &str) {
                 println!("cargo:rustc-link-lib=static=SDL2");
             }
 
-            // Additional SDL libraries are not supported when using "bundled"
+            // SDL_mixer is the only additional SDL library built by "bundled"
+            if cfg!(feature = "bundled") && cfg!(feature = "mixer") {
+                if target_os == "windows-msvc" {
+                    println!("cargo:rustc-link-lib=static=SDL2_mixer-static");
+                } else {
+                    println!("cargo:rustc-link-lib=static=SDL2_mixer");
+                }
+            }
+
             if !cfg!(feature = "bundled") {
                 if cfg!(feature = "gfx") {
                     println!("cargo:rustc-link-lib=static=SDL2_gfx");
@@ -480,7 +555,7 @@ fn copy_library_file(src_path: &Path, target_path: &Path) {
     }
 }
 
-fn copy_dynamic_libraries(sdl2_compiled_path: &Path, target_os: &str) {
+fn copy_dynamic_libraries(sdl2_compiled_path: &Path, target_os: &str, dll_name: &str) {
     let target_path = find_cargo_target_dir();
 
     // Windows binaries do not embed library search paths, so successfully
@@ -490,9 +565,8 @@ fn copy_dynamic_libraries(sdl2_compiled_path: &Path, target_os: &str) {
     // copy sdl2.dll out of its build tree and down to the top level cargo
     // binary output directory.
     if target_os.contains("windows") {
-        let sdl2_dll_name = "SDL2.dll";
         let sdl2_bin_path = sdl2_compiled_path.join("bin");
-        let src_dll_path = sdl2_bin_path.join(sdl2_dll_name);
+        let src_dll_path = sdl2_bin_path.join(dll_name);
 
         copy_library_file(&src_dll_path, &target_path);
     } else if target_os != "emscripten" {
@@ -532,6 +606,8 @@ fn main() {
     let sdl2_source_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("SDL");
 
     let sdl2_compiled_path: PathBuf;
+    #[cfg(all(feature = "bundled", feature = "mixer"))]
+    let sdl2_mixer_compiled_path: PathBuf;
     #[cfg(feature = "bundled")]
     {
         init_submodule(sdl2_source_path.as_path());
@@ -545,6 +621,23 @@ fn main() {
             "cargo:rustc-link-search={}",
             sdl2_compiled_path.join("lib").display()
         );
+
+        #[cfg(feature = "mixer")]
+        {
+            let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+            let sdl2_mixer_source_path = retrieve_sdl2_mixer(&out_path);
+            sdl2_mixer_compiled_path =
+                compile_sdl2_mixer(&sdl2_mixer_source_path, &sdl2_compiled_path, &out_path);
+
+            println!(
+                "cargo:rustc-link-search={}",
+                sdl2_mixer_compiled_path.join("lib64").display()
+            );
+            println!(
+                "cargo:rustc-link-search={}",
+                sdl2_mixer_compiled_path.join("lib").display()
+            );
+        }
     }
 
     let sdl2_includes = sdl2_source_path
@@ -556,10 +649,19 @@ fn main() {
     #[cfg(feature = "bindgen")]
     {
         let include_paths: Vec<String>;
-        #[cfg(feature = "bundled")]
+        #[cfg(all(feature = "bundled", not(feature = "mixer")))]
         {
             include_paths = vec![sdl2_includes];
         }
+        #[cfg(all(feature = "bundled", feature = "mixer"))]
+        {
+            // The installed headers provide the `SDL2/SDL_mixer.h` path of the wrapper
+            let sdl2_mixer_includes = sdl2_mixer_compiled_path.join("include");
+            include_paths = vec![
+                sdl2_includes,
+                sdl2_mixer_includes.to_str().unwrap().to_string(),
+            ];
+        }
         #[cfg(not(feature = "bundled"))]
         {
             include_paths = compute_include_paths(sdl2_includes)
@@ -582,7 +684,9 @@ fn main() {
         any(not(feature = "static-link"), target_os = "android")
     ))]
     {
-        copy_dynamic_libraries(&sdl2_compiled_path, target_os);
+        copy_dynamic_libraries(&sdl2_compiled_path, target_os, "SDL2.dll");
+        #[cfg(feature = "mixer")]
+        copy_dynamic_libraries(&sdl2_mixer_compiled_path, target_os, "SDL2_mixer.dll");
     }
 }
 
@@ -3,8 +3,8 @@
 use crate::*;
 
 pub const MIX_MAJOR_VERSION: u32 = 2;
-pub const MIX_MINOR_VERSION: u32 = 0;
-pub const MIX_PATCHLEVEL: u32 = 4;
+pub const MIX_MINOR_VERSION: u32 = 6;
+pub const MIX_PATCHLEVEL: u32 = 0;
 pub const MIX_CHANNELS: u32 = 8;
 pub const MIX_DEFAULT_FREQUENCY: u32 = 22050;
 pub const MIX_DEFAULT_FORMAT: u32 = 32784;
@@ -33,6 +33,7 @@ pub const MIX_InitFlags_MIX_INIT_MP3: MIX_InitFlags = 8;
 pub const MIX_InitFlags_MIX_INIT_OGG: MIX_InitFlags = 16;
 pub const MIX_InitFlags_MIX_INIT_MID: MIX_InitFlags = 32;
 pub const MIX_InitFlags_MIX_INIT_OPUS: MIX_InitFlags = 64;
+pub const MIX_InitFlags_MIX_INIT_WAVPACK: MIX_InitFlags = 128;
 pub type MIX_InitFlags = u32;
 extern "C" {
     pub fn Mix_Init(flags: libc::c_int) -> libc::c_int;
@@ -116,6 +117,8 @@ pub const Mix_MusicType_MUS_MP3_MAD_UNUSED: Mix_MusicType = 7;
 pub const Mix_MusicType_MUS_FLAC: Mix_MusicType = 8;
 pub const Mix_MusicType_MUS_MODPLUG_UNUSED: Mix_MusicType = 9;
 pub const Mix_MusicType_MUS_OPUS: Mix_MusicType = 10;
+pub const Mix_MusicType_MUS_WAVPACK: Mix_MusicType = 11;
+pub const Mix_MusicType_MUS_GME: Mix_MusicType = 12;
 pub type Mix_MusicType = u32;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -200,6 +203,21 @@ extern "C" {
 extern "C" {
     pub fn Mix_GetMusicType(music: *const Mix_Music) -> Mix_MusicType;
 }
+extern "C" {
+    pub fn Mix_GetMusicTitle(music: *const Mix_Music) -> *const libc::c_char;
+}
+extern "C" {
+    pub fn Mix_GetMusicTitleTag(music: *const Mix_Music) -> *const libc::c_char;
+}
+extern "C" {
+    pub fn Mix_GetMusicArtistTag(music: *const Mix_Music) -> *const libc::c_char;
+}
+extern "C" {
+    pub fn Mix_GetMusicAlbumTag(music: *const Mix_Music) -> *const libc::c_char;
+}
+extern "C" {
+    pub fn Mix_GetMusicCopyrightTag(music: *const Mix_Music) -> *const libc::c_char;
+}
 extern "C" {
     pub fn Mix_SetPostMix(
         mix_func: ::core::option::Option<
@@ -328,6 +346,12 @@ extern "C" {
 extern "C" {
     pub fn Mix_VolumeMusic(volume: libc::c_int) -> libc::c_int;
 }
+extern "C" {
+    pub fn Mix_GetMusicVolume(music: *mut Mix_Music) -> libc::c_int;
+}
+extern "C" {
+    pub fn Mix_MasterVolume(volume: libc::c_int) -> libc::c_int;
+}
 extern "C" {
     pub fn Mix_HaltChannel(channel: libc::c_int) -> libc::c_int;
 }
@@ -376,9 +400,33 @@ extern "C" {
 extern "C" {
     pub fn Mix_PausedMusic() -> libc::c_int;
 }
+extern "C" {
+    pub fn Mix_ModMusicJumpToOrder(order: libc::c_int) -> libc::c_int;
+}
+extern "C" {
+    pub fn Mix_StartTrack(music: *mut Mix_Music, track: libc::c_int) -> libc::c_int;
+}
+extern "C" {
+    pub fn Mix_GetNumTracks(music: *mut Mix_Music) -> libc::c_int;
+}
 extern "C" {
     pub fn Mix_SetMusicPosition(position: f64) -> libc::c_int;
 }
+extern "C" {
+    pub fn Mix_GetMusicPosition(music: *mut Mix_Music) -> f64;
+}
+extern "C" {
+    pub fn Mix_MusicDuration(music: *mut Mix_Music) -> f64;
+}
+extern "C" {
+    pub fn Mix_GetMusicLoopStartTime(music: *mut Mix_Music) -> f64;
+}
+extern "C" {
+    pub fn Mix_GetMusicLoopEndTime(music: *mut Mix_Music) -> f64;
+}
+extern "C" {
+    pub fn Mix_GetMusicLoopLengthTime(music: *mut Mix_Music) -> f64;
+}
 extern "C" {
     pub fn Mix_Playing(channel: libc::c_int) -> libc::c_int;
 }
@@ -53,13 +53,18 @@
 //! ```
 
 use libc::{c_char, c_int, c_void};
+use std::cell::UnsafeCell;
 use std::convert::TryFrom;
 use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::io;
 use std::marker::PhantomData;
 use std::mem;
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
 use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use crate::get_error;
 use crate::rwops::RWops;
@@ -123,6 +128,148 @@ impl AudioSubsystem {
         AudioQueue::open_queue(self, device, spec)
     }
 
+    /// Opens a new audio device for capture which uses queueing rather than a callback.
+    ///
+    /// Recorded samples are read with [AudioCaptureQueue.dequeue](audio/struct.AudioCaptureQueue.html#method.dequeue).
+    /// The device starts paused; call `resume` to begin recording.
+    #[inline]
+    pub fn open_capture_queue<'a, Channel, D>(
+        &self,
+        device: D,
+        spec: &AudioSpecDesired,
+    ) -> Result<AudioCaptureQueue<Channel>, String>
+    where
+        Channel: AudioFormatNum,
+        D: Into<Option<&'a str>>,
+    {
+        AudioCaptureQueue::open_capture_queue(self, device, spec)
+    }
+
+    /// Opens a new audio device for playback, letting SDL pick a different frequency,
+    /// channel count or buffer size if the hardware prefers one.
+    ///
+    /// The values SDL settled on are passed to `get_callback` and reported by
+    /// `AudioDevice::spec`. The sample format always matches `CB::Channel`, so
+    /// `AllowedChanges::FORMAT` is ignored; use
+    /// [open_playback_dynamic](#method.open_playback_dynamic) to accept the device's
+    /// native format.
+    pub fn open_playback_with_changes<'a, CB, F, D>(
+        &self,
+        device: D,
+        spec: &AudioSpecDesired,
+        allowed_changes: AllowedChanges,
+        get_callback: F,
+    ) -> Result<AudioDevice<CB>, String>
+    where
+        CB: AudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        AudioDevice::open_playback_with_changes(self, device, spec, allowed_changes, get_callback)
+    }
+
+    /// Opens a new audio device for capture, letting SDL pick a different frequency,
+    /// channel count or buffer size if the hardware prefers one.
+    ///
+    /// See [open_playback_with_changes](#method.open_playback_with_changes).
+    pub fn open_capture_with_changes<'a, CB, F, D>(
+        &self,
+        device: D,
+        spec: &AudioSpecDesired,
+        allowed_changes: AllowedChanges,
+        get_callback: F,
+    ) -> Result<AudioDevice<CB>, String>
+    where
+        CB: AudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        AudioDevice::open_capture_with_changes(self, device, spec, allowed_changes, get_callback)
+    }
+
+    /// Opens a new audio device for playback whose sample format is only known once
+    /// it has been opened.
+    ///
+    /// `format` is the requested format; if `allowed_changes` contains
+    /// `AllowedChanges::FORMAT` the device may use another one, which is then
+    /// reported in the `AudioSpec` passed to `get_callback`.
+    pub fn open_playback_dynamic<'a, CB, F, D>(
+        &self,
+        device: D,
+        spec: &AudioSpecDesired,
+        format: AudioFormat,
+        allowed_changes: AllowedChanges,
+        get_callback: F,
+    ) -> Result<AudioDevice<DynamicAudio<CB>>, String>
+    where
+        CB: DynamicAudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        AudioDevice::open_playback_dynamic(
+            self,
+            device,
+            spec,
+            format,
+            allowed_changes,
+            get_callback,
+        )
+    }
+
+    /// Opens a new audio device for capture whose sample format is only known once
+    /// it has been opened.
+    ///
+    /// See [open_playback_dynamic](#method.open_playback_dynamic).
+    pub fn open_capture_dynamic<'a, CB, F, D>(
+        &self,
+        device: D,
+        spec: &AudioSpecDesired,
+        format: AudioFormat,
+        allowed_changes: AllowedChanges,
+        get_callback: F,
+    ) -> Result<AudioDevice<DynamicAudio<CB>>, String>
+    where
+        CB: DynamicAudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        AudioDevice::open_capture_dynamic(self, device, spec, format, allowed_changes, get_callback)
+    }
+
+    /// Opens a new queueing audio device for playback, letting SDL pick a different
+    /// frequency, channel count or buffer size if the hardware prefers one.
+    ///
+    /// The sample format always matches `Channel`, so `AllowedChanges::FORMAT` is ignored.
+    pub fn open_queue_with_changes<'a, Channel, D>(
+        &self,
+        device: D,
+        spec: &AudioSpecDesired,
+        allowed_changes: AllowedChanges,
+    ) -> Result<AudioQueue<Channel>, String>
+    where
+        Channel: AudioFormatNum,
+        D: Into<Option<&'a str>>,
+    {
+        AudioQueue::open_queue_with_changes(self, device, spec, allowed_changes)
+    }
+
+    /// Opens a new queueing audio device for capture, letting SDL pick a different
+    /// frequency, channel count or buffer size if the hardware prefers one.
+    ///
+    /// The sample format always matches `Channel`, so `AllowedChanges::FORMAT` is ignored.
+    pub fn open_capture_queue_with_changes<'a, Channel, D>(
+        &self,
+        device: D,
+        spec: &AudioSpecDesired,
+        allowed_changes: AllowedChanges,
+    ) -> Result<AudioCaptureQueue<Channel>, String>
+    where
+        Channel: AudioFormatNum,
+        D: Into<Option<&'a str>>,
+    {
+        AudioCaptureQueue::open_capture_queue_with_changes(self, device, spec, allowed_changes)
+    }
+
     #[doc(alias = "SDL_GetCurrentAudioDriver")]
     pub fn current_audio_driver(&self) -> &'static str {
         unsafe {
@@ -157,74 +304,181 @@ impl AudioSubsystem {
 
     #[doc(alias = "SDL_GetAudioDeviceName")]
     pub fn audio_playback_device_name(&self, index: u32) -> Result<String, String> {
-        unsafe {
-            let dev_name = sys::SDL_GetAudioDeviceName(index as c_int, 0);
-            if dev_name.is_null() {
-                Err(get_error())
-            } else {
-                let cstr = CStr::from_ptr(dev_name as *const _);
-                Ok(cstr.to_str().unwrap().to_owned())
-            }
-        }
+        audio_device_name(index, false)
     }
 
     #[doc(alias = "SDL_GetAudioDeviceName")]
     pub fn audio_capture_device_name(&self, index: u32) -> Result<String, String> {
-        unsafe {
-            let dev_name = sys::SDL_GetAudioDeviceName(index as c_int, 1);
-            if dev_name.is_null() {
-                Err(get_error())
-            } else {
-                let cstr = CStr::from_ptr(dev_name as *const _);
-                Ok(cstr.to_str().unwrap().to_owned())
-            }
-        }
+        audio_device_name(index, true)
     }
 
     #[doc(alias = "SDL_GetAudioDeviceSpec")]
     pub fn audio_playback_device_spec(&self, index: u32) -> Result<AudioSpec, String> {
-        let mut spec = sys::SDL_AudioSpec {
-            freq: 0,
-            format: 0,
-            channels: 0,
-            silence: 0,
-            samples: 0,
-            padding: 0,
-            size: 0,
-            callback: None,
-            userdata: ptr::null_mut(),
-        };
+        audio_device_spec(index, false)
+    }
+    #[doc(alias = "SDL_GetAudioDeviceSpec")]
+    pub fn audio_capture_device_spec(&self, index: u32) -> Result<AudioSpec, String> {
+        audio_device_spec(index, true)
+    }
+
+    /// Gets an iterator over the playback devices currently available.
+    ///
+    /// The list is re-detected every time this is called.
+    #[doc(alias = "SDL_GetNumAudioDevices")]
+    pub fn playback_devices(&self) -> AudioDeviceIterator {
+        AudioDeviceIterator::new(false)
+    }
+
+    /// Gets an iterator over the capture devices currently available.
+    ///
+    /// The list is re-detected every time this is called.
+    #[doc(alias = "SDL_GetNumAudioDevices")]
+    pub fn capture_devices(&self) -> AudioDeviceIterator {
+        AudioDeviceIterator::new(true)
+    }
+
+    /// Gets the name and preferred spec of the default playback device.
+    ///
+    /// This may query the sound server and should not be called every frame.
+    /// Supported since SDL 2.24.0
+    #[doc(alias = "SDL_GetDefaultAudioInfo")]
+    pub fn default_playback_device(&self) -> Result<AudioDeviceInfo, String> {
+        default_audio_device(false)
+    }
+
+    /// Gets the name and preferred spec of the default capture device.
+    ///
+    /// This may query the sound server and should not be called every frame.
+    /// Supported since SDL 2.24.0
+    #[doc(alias = "SDL_GetDefaultAudioInfo")]
+    pub fn default_capture_device(&self) -> Result<AudioDeviceInfo, String> {
+        default_audio_device(true)
+    }
+}
+
+fn empty_ll_audio_spec() -> sys::SDL_AudioSpec {
+    sys::SDL_AudioSpec {
+        freq: 0,
+        format: 0,
+        channels: 0,
+        silence: 0,
+        samples: 0,
+        padding: 0,
+        size: 0,
+        callback: None,
+        userdata: ptr::null_mut(),
+    }
+}
 
-        let result = unsafe { sys::SDL_GetAudioDeviceSpec(index as c_int, 0, &mut spec) };
-        if result != 0 {
+fn audio_device_name(index: u32, capture: bool) -> Result<String, String> {
+    unsafe {
+        let dev_name = sys::SDL_GetAudioDeviceName(index as c_int, capture as c_int);
+        if dev_name.is_null() {
             Err(get_error())
         } else {
-            Ok(AudioSpec::convert_from_ll(spec))
+            let cstr = CStr::from_ptr(dev_name as *const _);
+            Ok(cstr.to_string_lossy().into_owned())
         }
     }
-    #[doc(alias = "SDL_GetAudioDeviceSpec")]
-    pub fn audio_capture_device_spec(&self, index: u32) -> Result<AudioSpec, String> {
-        let mut spec = sys::SDL_AudioSpec {
-            freq: 0,
-            format: 0,
-            channels: 0,
-            silence: 0,
-            samples: 0,
-            padding: 0,
-            size: 0,
-            callback: None,
-            userdata: ptr::null_mut(),
-        };
+}
 
-        let result = unsafe { sys::SDL_GetAudioDeviceSpec(index as c_int, 1, &mut spec) };
-        if result != 0 {
-            Err(get_error())
+fn audio_device_spec(index: u32, capture: bool) -> Result<AudioSpec, String> {
+    let mut spec = empty_ll_audio_spec();
+
+    let result =
+        unsafe { sys::SDL_GetAudioDeviceSpec(index as c_int, capture as c_int, &mut spec) };
+    if result != 0 {
+        Err(get_error())
+    } else {
+        AudioSpec::try_from_ll(spec)
+            .ok_or_else(|| format!("Unknown audio format {:#x}", spec.format))
+    }
+}
+
+fn default_audio_device(capture: bool) -> Result<AudioDeviceInfo, String> {
+    let mut name: *mut c_char = ptr::null_mut();
+    let mut spec = empty_ll_audio_spec();
+
+    unsafe {
+        if sys::SDL_GetDefaultAudioInfo(&mut name, &mut spec, capture as c_int) != 0 {
+            return Err(get_error());
+        }
+        let device_name = if name.is_null() {
+            String::new()
         } else {
-            Ok(AudioSpec::convert_from_ll(spec))
+            let device_name = CStr::from_ptr(name).to_string_lossy().into_owned();
+            sys::SDL_free(name as *mut c_void);
+            device_name
+        };
+
+        Ok(AudioDeviceInfo {
+            name: device_name,
+            spec: AudioSpec::try_from_ll(spec),
+            is_capture: capture,
+        })
+    }
+}
+
+/// The name and preferred format of an audio device.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AudioDeviceInfo {
+    /// The name to pass to `open_playback`, `open_queue` and friends.
+    /// May be empty for a default device without a name.
+    pub name: String,
+    /// The device's preferred spec, if SDL can tell.
+    /// Only `freq`, `format` and `channels` are meaningful.
+    pub spec: Option<AudioSpec>,
+    pub is_capture: bool,
+}
+
+/// Iterates over the playback or capture devices, in the order SDL lists them.
+///
+/// Devices that disappear while iterating are skipped.
+pub struct AudioDeviceIterator {
+    capture: bool,
+    index: u32,
+    length: u32,
+}
+
+impl AudioDeviceIterator {
+    fn new(capture: bool) -> AudioDeviceIterator {
+        let length = unsafe { sys::SDL_GetNumAudioDevices(capture as c_int) };
+        AudioDeviceIterator {
+            capture,
+            index: 0,
+            // A negative count means SDL cannot list the devices; iterate over none.
+            length: length.max(0) as u32,
         }
     }
 }
 
+impl Iterator for AudioDeviceIterator {
+    type Item = AudioDeviceInfo;
+
+    fn next(&mut self) -> Option<AudioDeviceInfo> {
+        while self.index < self.length {
+            let index = self.index;
+            self.index += 1;
+
+            if let Ok(name) = audio_device_name(index, self.capture) {
+                return Some(AudioDeviceInfo {
+                    name,
+                    spec: audio_device_spec(index, self.capture).ok(),
+                    is_capture: self.capture,
+                });
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.length - self.index) as usize))
+    }
+}
+
+impl std::iter::FusedIterator for AudioDeviceIterator {}
+
 #[repr(i32)]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub enum AudioFormat {
@@ -441,6 +695,163 @@ pub fn drivers() -> DriverIterator {
     }
 }
 
+/// A speaker position, as used by [`ChannelLayout`](enum.ChannelLayout.html).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Speaker {
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    LowFrequency,
+    BackLeft,
+    BackRight,
+    SideLeft,
+    SideRight,
+}
+
+/// A common speaker arrangement, in the order SDL interleaves its channels.
+///
+/// Use [`channels`](#method.channels) for `AudioSpecDesired::channels` and
+/// [`index_of`](#method.index_of) to find a speaker within a frame:
+///
+/// ```
+/// use sdl2::audio::{frames_mut, ChannelLayout, Speaker};
+///
+/// let layout = ChannelLayout::Surround51;
+/// let lfe = layout.index_of(Speaker::LowFrequency).unwrap();
+///
+/// let mut out = [0.0f32; 6 * 4];
+/// for frame in frames_mut::<_, 6>(&mut out) {
+///     frame[lfe] = 0.5;
+/// }
+/// assert_eq!(out[3], 0.5);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ChannelLayout {
+    /// 1 channel: FC
+    Mono,
+    /// 2 channels: FL FR
+    Stereo,
+    /// 4 channels: FL FR BL BR
+    Quad,
+    /// 6 channels: FL FR FC LFE SL SR. SDL also uses these for BL BR.
+    Surround51,
+    /// 8 channels: FL FR FC LFE BL BR SL SR
+    Surround71,
+}
+
+impl ChannelLayout {
+    /// The layout SDL assumes for the given channel count, if it is one of the above.
+    pub fn from_channels(channels: u8) -> Option<ChannelLayout> {
+        match channels {
+            1 => Some(ChannelLayout::Mono),
+            2 => Some(ChannelLayout::Stereo),
+            4 => Some(ChannelLayout::Quad),
+            6 => Some(ChannelLayout::Surround51),
+            8 => Some(ChannelLayout::Surround71),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn channels(self) -> u8 {
+        self.speakers().len() as u8
+    }
+
+    /// The speakers in the order their samples appear in each frame.
+    pub fn speakers(self) -> &'static [Speaker] {
+        use self::Speaker::*;
+        match self {
+            ChannelLayout::Mono => &[FrontCenter],
+            ChannelLayout::Stereo => &[FrontLeft, FrontRight],
+            ChannelLayout::Quad => &[FrontLeft, FrontRight, BackLeft, BackRight],
+            ChannelLayout::Surround51 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                SideLeft,
+                SideRight,
+            ],
+            ChannelLayout::Surround71 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                BackLeft,
+                BackRight,
+                SideLeft,
+                SideRight,
+            ],
+        }
+    }
+
+    /// The position of `speaker` within a frame, or `None` if the layout lacks it.
+    pub fn index_of(self, speaker: Speaker) -> Option<usize> {
+        self.speakers().iter().position(|&s| s == speaker)
+    }
+}
+
+/// Iterates over the frames of an interleaved buffer with `N` channels.
+/// A trailing partial frame is skipped.
+pub fn frames<T, const N: usize>(buf: &[T]) -> impl Iterator<Item = &[T; N]> {
+    buf.chunks_exact(N)
+        .map(|frame| <&[T; N]>::try_from(frame).unwrap())
+}
+
+/// Iterates mutably over the frames of an interleaved buffer with `N` channels,
+/// such as the one passed to `AudioCallback::callback`. A trailing partial frame is skipped.
+pub fn frames_mut<T, const N: usize>(buf: &mut [T]) -> impl Iterator<Item = &mut [T; N]> {
+    buf.chunks_exact_mut(N)
+        .map(|frame| <&mut [T; N]>::try_from(frame).unwrap())
+}
+
+/// Interleaves one buffer per channel into `out`, returning the number of frames written.
+///
+/// Stops at the end of the shortest channel or of `out`.
+pub fn interleave<T: Copy>(planar: &[&[T]], out: &mut [T]) -> usize {
+    let channels = planar.len();
+    if channels == 0 {
+        return 0;
+    }
+    let frames = planar
+        .iter()
+        .map(|channel| channel.len())
+        .min()
+        .unwrap_or(0)
+        .min(out.len() / channels);
+
+    for (frame, samples) in out.chunks_exact_mut(channels).take(frames).enumerate() {
+        for (sample, channel) in samples.iter_mut().zip(planar) {
+            *sample = channel[frame];
+        }
+    }
+    frames
+}
+
+/// Splits an interleaved buffer into one buffer per channel, returning the number of
+/// frames written.
+///
+/// Stops at the end of `interleaved` or of the shortest channel buffer.
+pub fn deinterleave<T: Copy>(interleaved: &[T], planar: &mut [&mut [T]]) -> usize {
+    let channels = planar.len();
+    if channels == 0 {
+        return 0;
+    }
+    let frames = planar
+        .iter()
+        .map(|channel| channel.len())
+        .min()
+        .unwrap_or(0)
+        .min(interleaved.len() / channels);
+
+    for (frame, samples) in interleaved.chunks_exact(channels).take(frames).enumerate() {
+        for (&sample, channel) in samples.iter().zip(planar.iter_mut()) {
+            channel[frame] = sample;
+        }
+    }
+    frames
+}
+
 pub struct AudioSpecWAV {
     pub freq: i32,
     pub format: AudioFormat,
@@ -496,6 +907,177 @@ impl AudioSpecWAV {
             from_raw_parts(ptr, len)
         }
     }
+
+    /// Gets the audio data as samples of type `T`, or `None` if `T` does not match
+    /// the format of the WAVE.
+    pub fn samples<T: AudioFormatNum>(&self) -> Option<&[T]> {
+        use std::slice::from_raw_parts;
+
+        if self.format != T::audio_format() {
+            return None;
+        }
+        let len = self.audio_len as usize / mem::size_of::<T>();
+        if len == 0 {
+            return Some(&[]);
+        }
+        // SDL allocates the buffer with SDL_malloc, so this only fails for unusual allocators.
+        if self.audio_buf.align_offset(mem::align_of::<T>()) != 0 {
+            return None;
+        }
+        unsafe { Some(from_raw_parts(self.audio_buf as *const T, len)) }
+    }
+}
+
+/// Writes PCM samples of type `T` to a WAVE file.
+///
+/// The header is written up front and its sizes are patched by
+/// [`flush`](#method.flush), [`finalize`](#method.finalize) or when the writer is
+/// dropped, so samples can be streamed in as they arrive, for example from a capture
+/// callback. WAVE files support `u8`, `i16`, `i32` and `f32` samples.
+///
+/// Any `Write + Seek` destination works, including `RWops`:
+///
+/// ```no_run
+/// use sdl2::audio::WavWriter;
+/// use sdl2::rwops::RWops;
+///
+/// let file = RWops::from_file("tone.wav", "wb").unwrap();
+/// let mut writer = WavWriter::<_, i16>::new(file, 44100, 1).unwrap();
+/// let tone: Vec<i16> = (0..44100).map(|i| ((i as f32 * 0.06).sin() * 8000.0) as i16).collect();
+/// writer.write_samples(&tone).unwrap();
+/// writer.finalize().unwrap();
+/// ```
+pub struct WavWriter<W: io::Write + io::Seek, T: AudioFormatNum> {
+    writer: Option<W>,
+    start: u64,
+    data_len: u32,
+    _marker: PhantomData<T>,
+}
+
+const WAV_HEADER_LEN: u32 = 44;
+const WAV_FORMAT_PCM: u16 = 1;
+const WAV_FORMAT_IEEE_FLOAT: u16 = 3;
+
+impl<T: AudioFormatNum> WavWriter<io::BufWriter<File>, T> {
+    /// Creates (or truncates) the file at `path` and writes the WAVE header.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        freq: i32,
+        channels: u8,
+    ) -> Result<WavWriter<io::BufWriter<File>, T>, String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        WavWriter::new(io::BufWriter::new(file), freq, channels)
+    }
+}
+
+impl<W: io::Write + io::Seek, T: AudioFormatNum> WavWriter<W, T> {
+    /// Writes the WAVE header at the current position of `writer`.
+    pub fn new(mut writer: W, freq: i32, channels: u8) -> Result<WavWriter<W, T>, String> {
+        let format_tag = match T::audio_format() {
+            AudioFormat::U8 => WAV_FORMAT_PCM,
+            f if f == AudioFormat::s16_sys() || f == AudioFormat::s32_sys() => WAV_FORMAT_PCM,
+            f if f == AudioFormat::f32_sys() => WAV_FORMAT_IEEE_FLOAT,
+            f => return Err(format!("WAVE files cannot hold {:?} samples", f)),
+        };
+        if freq <= 0 || channels == 0 {
+            return Err("Invalid WAVE frequency or channel count".to_owned());
+        }
+
+        let sample_size = mem::size_of::<T>() as u32;
+        let block_align = sample_size * channels as u32;
+        let start = writer.stream_position().map_err(|e| e.to_string())?;
+
+        let mut header = Vec::with_capacity(WAV_HEADER_LEN as usize);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&(WAV_HEADER_LEN - 8).to_le_bytes());
+        header.extend_from_slice(b"WAVEfmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&format_tag.to_le_bytes());
+        header.extend_from_slice(&(channels as u16).to_le_bytes());
+        header.extend_from_slice(&(freq as u32).to_le_bytes());
+        header.extend_from_slice(&(freq as u32 * block_align).to_le_bytes());
+        header.extend_from_slice(&(block_align as u16).to_le_bytes());
+        header.extend_from_slice(&(sample_size as u16 * 8).to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&0u32.to_le_bytes());
+        writer.write_all(&header).map_err(|e| e.to_string())?;
+
+        Ok(WavWriter {
+            writer: Some(writer),
+            start,
+            data_len: 0,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Appends interleaved samples.
+    pub fn write_samples(&mut self, samples: &[T]) -> Result<(), String> {
+        use std::slice::from_raw_parts;
+
+        let len = u32::try_from(mem::size_of_val(samples))
+            .ok()
+            .and_then(|len| len.checked_add(self.data_len))
+            .filter(|&len| len <= u32::MAX - WAV_HEADER_LEN)
+            .ok_or_else(|| "WAVE file size limit exceeded".to_owned())?;
+
+        let mut bytes =
+            unsafe { from_raw_parts(samples.as_ptr() as *const u8, mem::size_of_val(samples)) };
+        // WAVE data is little-endian.
+        let swapped: Vec<u8>;
+        if cfg!(target_endian = "big") && mem::size_of::<T>() > 1 {
+            swapped = bytes
+                .chunks_exact(mem::size_of::<T>())
+                .flat_map(|sample| sample.iter().rev().copied())
+                .collect();
+            bytes = &swapped;
+        }
+
+        self.writer
+            .as_mut()
+            .expect("Missing writer")
+            .write_all(bytes)
+            .map_err(|e| e.to_string())?;
+        self.data_len = len;
+        Ok(())
+    }
+
+    /// The number of bytes of sample data written so far.
+    pub fn data_len(&self) -> u32 {
+        self.data_len
+    }
+
+    fn write_sizes(&mut self) -> io::Result<()> {
+        let writer = self.writer.as_mut().expect("Missing writer");
+        let end = writer.stream_position()?;
+
+        writer.seek(io::SeekFrom::Start(self.start + 4))?;
+        writer.write_all(&(WAV_HEADER_LEN - 8 + self.data_len).to_le_bytes())?;
+        writer.seek(io::SeekFrom::Start(self.start + WAV_HEADER_LEN as u64 - 4))?;
+        writer.write_all(&self.data_len.to_le_bytes())?;
+        writer.seek(io::SeekFrom::Start(end))?;
+        writer.flush()
+    }
+
+    /// Patches the header with the sizes written so far and flushes the writer, so the
+    /// file is valid even if the program stops before `finalize`.
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.write_sizes().map_err(|e| e.to_string())
+    }
+
+    /// Patches the header and returns the underlying writer.
+    pub fn finalize(mut self) -> Result<W, String> {
+        self.write_sizes().map_err(|e| e.to_string())?;
+        Ok(self.writer.take().expect("Missing writer"))
+    }
+}
+
+impl<W: io::Write + io::Seek, T: AudioFormatNum> Drop for WavWriter<W, T> {
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            // Errors cannot be reported here; call `finalize` to handle them.
+            let _ = self.write_sizes();
+        }
+    }
 }
 
 impl Drop for AudioSpecWAV {
@@ -516,38 +1098,125 @@ where
     fn callback(&mut self, _: &mut [Self::Channel]);
 }
 
-/// A phantom type for retrieving the `SDL_AudioFormat` of a given generic type.
-/// All format types are returned as native-endian.
-pub trait AudioFormatNum {
-    fn audio_format() -> AudioFormat;
-
-    /// The appropriately typed silence value for the audio format used.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// // The AudioFormatNum trait has to be imported for the Channel::SILENCE part to work.
-    /// use sdl2::audio::{AudioCallback, AudioFormatNum};
-    ///
-    /// struct Silence;
-    ///
-    /// impl AudioCallback for Silence {
-    ///     type Channel = u16;
-    ///
-    ///     fn callback(&mut self, out: &mut [u16]) {
-    ///         for dst in out.iter_mut() {
-    ///             *dst = Self::Channel::SILENCE;
-    ///         }
-    ///     }
-    /// }
-    /// ```
-    const SILENCE: Self;
+/// A buffer of samples in the format a device was actually opened with.
+pub enum AudioBufferMut<'a> {
+    U8(&'a mut [u8]),
+    S8(&'a mut [i8]),
+    U16(&'a mut [u16]),
+    S16(&'a mut [i16]),
+    S32(&'a mut [i32]),
+    F32(&'a mut [f32]),
+    /// Samples in the non-native byte order, left as raw bytes.
+    Foreign(AudioFormat, &'a mut [u8]),
 }
 
-/// `AUDIO_S8`
-impl AudioFormatNum for i8 {
-    fn audio_format() -> AudioFormat {
-        AudioFormat::S8
+impl<'a> AudioBufferMut<'a> {
+    fn new(format: AudioFormat, buf: &'a mut [u8]) -> AudioBufferMut<'a> {
+        unsafe fn cast<T: AudioFormatNum>(buf: &mut [u8]) -> &mut [T] {
+            // SDL's audio buffers are allocated with `SDL_malloc` and suitably aligned.
+            debug_assert_eq!(buf.as_ptr() as usize % mem::align_of::<T>(), 0);
+            std::slice::from_raw_parts_mut(
+                buf.as_mut_ptr() as *mut T,
+                buf.len() / mem::size_of::<T>(),
+            )
+        }
+
+        unsafe {
+            match format {
+                AudioFormat::U8 => AudioBufferMut::U8(buf),
+                AudioFormat::S8 => AudioBufferMut::S8(cast(buf)),
+                f if f == AudioFormat::u16_sys() => AudioBufferMut::U16(cast(buf)),
+                f if f == AudioFormat::s16_sys() => AudioBufferMut::S16(cast(buf)),
+                f if f == AudioFormat::s32_sys() => AudioBufferMut::S32(cast(buf)),
+                f if f == AudioFormat::f32_sys() => AudioBufferMut::F32(cast(buf)),
+                f => AudioBufferMut::Foreign(f, buf),
+            }
+        }
+    }
+}
+
+/// Like `AudioCallback`, but for devices whose sample format is chosen when they are opened.
+pub trait DynamicAudioCallback: Send {
+    fn callback(&mut self, _: AudioBufferMut);
+}
+
+/// The callback data of a device opened with `open_playback_dynamic` or `open_capture_dynamic`.
+///
+/// Dereferences to the wrapped `DynamicAudioCallback`.
+pub struct DynamicAudio<CB> {
+    format: AudioFormat,
+    callback: CB,
+}
+
+impl<CB> DynamicAudio<CB> {
+    fn new(format: AudioFormat, callback: CB) -> DynamicAudio<CB> {
+        DynamicAudio { format, callback }
+    }
+
+    /// The sample format the device was opened with.
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    pub fn into_inner(self) -> CB {
+        self.callback
+    }
+}
+
+impl<CB: DynamicAudioCallback> AudioCallback for DynamicAudio<CB> {
+    type Channel = u8;
+
+    fn callback(&mut self, out: &mut [u8]) {
+        self.callback
+            .callback(AudioBufferMut::new(self.format, out));
+    }
+}
+
+impl<CB> Deref for DynamicAudio<CB> {
+    type Target = CB;
+    fn deref(&self) -> &CB {
+        &self.callback
+    }
+}
+
+impl<CB> DerefMut for DynamicAudio<CB> {
+    fn deref_mut(&mut self) -> &mut CB {
+        &mut self.callback
+    }
+}
+
+/// A phantom type for retrieving the `SDL_AudioFormat` of a given generic type.
+/// All format types are returned as native-endian.
+pub trait AudioFormatNum {
+    fn audio_format() -> AudioFormat;
+
+    /// The appropriately typed silence value for the audio format used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // The AudioFormatNum trait has to be imported for the Channel::SILENCE part to work.
+    /// use sdl2::audio::{AudioCallback, AudioFormatNum};
+    ///
+    /// struct Silence;
+    ///
+    /// impl AudioCallback for Silence {
+    ///     type Channel = u16;
+    ///
+    ///     fn callback(&mut self, out: &mut [u16]) {
+    ///         for dst in out.iter_mut() {
+    ///             *dst = Self::Channel::SILENCE;
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    const SILENCE: Self;
+}
+
+/// `AUDIO_S8`
+impl AudioFormatNum for i8 {
+    fn audio_format() -> AudioFormat {
+        AudioFormat::S8
     }
     const SILENCE: i8 = 0;
 }
@@ -607,6 +1276,19 @@ extern "C" fn audio_callback_marshall<CB: AudioCallback>(
     }
 }
 
+bitflags! {
+    /// The properties SDL may change when the hardware cannot provide the desired spec.
+    ///
+    /// Any difference that is not allowed is converted by SDL behind the scenes.
+    pub struct AllowedChanges: u32 {
+        const FREQUENCY = sys::SDL_AUDIO_ALLOW_FREQUENCY_CHANGE;
+        const FORMAT = sys::SDL_AUDIO_ALLOW_FORMAT_CHANGE;
+        const CHANNELS = sys::SDL_AUDIO_ALLOW_CHANNELS_CHANGE;
+        const SAMPLES = sys::SDL_AUDIO_ALLOW_SAMPLES_CHANGE;
+        const ANY = sys::SDL_AUDIO_ALLOW_ANY_CHANGE;
+    }
+}
+
 #[derive(Clone)]
 pub struct AudioSpecDesired {
     /// DSP frequency (samples per second). Set to None for the device's fallback frequency.
@@ -620,6 +1302,7 @@ pub struct AudioSpecDesired {
 impl AudioSpecDesired {
     fn convert_to_ll<CB, F, C, S>(
         freq: F,
+        format: AudioFormat,
         channels: C,
         samples: S,
         userdata: *mut Option<CB>,
@@ -648,7 +1331,7 @@ impl AudioSpecDesired {
 
         sys::SDL_AudioSpec {
             freq: freq.unwrap_or(0),
-            format: <CB::Channel as AudioFormatNum>::audio_format().to_ll(),
+            format: format.to_ll(),
             channels: channels.unwrap_or(0),
             silence: 0,
             samples: samples.unwrap_or(0),
@@ -715,15 +1398,24 @@ pub struct AudioSpec {
 }
 
 impl AudioSpec {
-    fn convert_from_ll(spec: sys::SDL_AudioSpec) -> AudioSpec {
-        AudioSpec {
+    /// The speaker layout of the obtained channel count, if it is one of the common ones.
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        ChannelLayout::from_channels(self.channels)
+    }
+
+    fn try_from_ll(spec: sys::SDL_AudioSpec) -> Option<AudioSpec> {
+        Some(AudioSpec {
             freq: spec.freq,
-            format: AudioFormat::from_ll(spec.format).unwrap(),
+            format: AudioFormat::from_ll(spec.format)?,
             channels: spec.channels,
             silence: spec.silence,
             samples: spec.samples,
             size: spec.size,
-        }
+        })
+    }
+
+    fn convert_from_ll(spec: sys::SDL_AudioSpec) -> AudioSpec {
+        AudioSpec::try_from_ll(spec).unwrap()
     }
 }
 
@@ -756,12 +1448,34 @@ pub struct AudioQueue<Channel: AudioFormatNum> {
 }
 
 impl<'a, Channel: AudioFormatNum> AudioQueue<Channel> {
-    /// Opens a new audio device given the desired parameters and callback.
-    #[doc(alias = "SDL_OpenAudioDevice")]
+    /// Opens a new audio device for playback given the desired parameters.
     pub fn open_queue<D: Into<Option<&'a str>>>(
         a: &AudioSubsystem,
         device: D,
         spec: &AudioSpecDesired,
+    ) -> Result<AudioQueue<Channel>, String> {
+        AudioQueue::open(a, device, spec, false, AllowedChanges::empty())
+    }
+
+    /// Opens a new audio device for playback, allowing SDL to change the given
+    /// properties of the spec. `AllowedChanges::FORMAT` is ignored.
+    pub fn open_queue_with_changes<D: Into<Option<&'a str>>>(
+        a: &AudioSubsystem,
+        device: D,
+        spec: &AudioSpecDesired,
+        allowed_changes: AllowedChanges,
+    ) -> Result<AudioQueue<Channel>, String> {
+        AudioQueue::open(a, device, spec, false, allowed_changes)
+    }
+
+    /// Opens a new audio device for playback or capture (given the desired parameters).
+    #[doc(alias = "SDL_OpenAudioDevice")]
+    fn open<D: Into<Option<&'a str>>>(
+        a: &AudioSubsystem,
+        device: D,
+        spec: &AudioSpecDesired,
+        capture: bool,
+        allowed_changes: AllowedChanges,
     ) -> Result<AudioQueue<Channel>, String> {
         use std::mem::MaybeUninit;
 
@@ -780,13 +1494,14 @@ impl<'a, Channel: AudioFormatNum> AudioQueue<Channel> {
             // device to an Option<&_> first.
             let device_ptr = device.as_ref().map_or(ptr::null(), |s| s.as_ptr());
 
-            let iscapture_flag = 0;
+            let iscapture_flag = if capture { 1 } else { 0 };
             let device_id = sys::SDL_OpenAudioDevice(
                 device_ptr as *const c_char,
                 iscapture_flag,
                 &desired,
                 obtained.as_mut_ptr(),
-                0,
+                // The samples handed to us must stay in the requested `Channel` format.
+                (allowed_changes - AllowedChanges::FORMAT).bits() as c_int,
             );
             match device_id {
                 0 => Err(get_error()),
@@ -882,6 +1597,99 @@ impl<'a, Channel: AudioFormatNum> AudioQueue<Channel> {
             sys::SDL_ClearQueuedAudio(self.device_id.id());
         }
     }
+
+    /// Returns the number of samples still waiting to be played. See `size` for the byte
+    /// count.
+    #[doc(alias = "SDL_GetQueuedAudioSize")]
+    pub fn queued_size(&self) -> usize {
+        self.size() as usize / mem::size_of::<Channel>()
+    }
+}
+
+/// A capture device whose recorded samples are read with `dequeue`.
+///
+/// Unlike [`AudioQueue`], samples cannot be queued to it.
+pub struct AudioCaptureQueue<Channel: AudioFormatNum> {
+    queue: AudioQueue<Channel>,
+}
+
+impl<'a, Channel: AudioFormatNum> AudioCaptureQueue<Channel> {
+    /// Opens a new audio device for capture given the desired parameters.
+    pub fn open_capture_queue<D: Into<Option<&'a str>>>(
+        a: &AudioSubsystem,
+        device: D,
+        spec: &AudioSpecDesired,
+    ) -> Result<AudioCaptureQueue<Channel>, String> {
+        AudioCaptureQueue::open_capture_queue_with_changes(a, device, spec, AllowedChanges::empty())
+    }
+
+    /// Opens a new audio device for capture, allowing SDL to change the given
+    /// properties of the spec. `AllowedChanges::FORMAT` is ignored.
+    pub fn open_capture_queue_with_changes<D: Into<Option<&'a str>>>(
+        a: &AudioSubsystem,
+        device: D,
+        spec: &AudioSpecDesired,
+        allowed_changes: AllowedChanges,
+    ) -> Result<AudioCaptureQueue<Channel>, String> {
+        let queue = AudioQueue::open(a, device, spec, true, allowed_changes)?;
+        Ok(AudioCaptureQueue { queue })
+    }
+
+    #[inline]
+    pub fn subsystem(&self) -> &AudioSubsystem {
+        self.queue.subsystem()
+    }
+
+    #[inline]
+    pub fn spec(&self) -> &AudioSpec {
+        self.queue.spec()
+    }
+
+    #[doc(alias = "SDL_GetAudioDeviceStatus")]
+    pub fn status(&self) -> AudioStatus {
+        self.queue.status()
+    }
+
+    /// Pauses recording.
+    #[doc(alias = "SDL_PauseAudioDevice")]
+    pub fn pause(&self) {
+        self.queue.pause()
+    }
+
+    /// Starts recording.
+    #[doc(alias = "SDL_PauseAudioDevice")]
+    pub fn resume(&self) {
+        self.queue.resume()
+    }
+
+    /// Reads recorded samples into `buf`, returning the number of samples written.
+    ///
+    /// This never blocks; if fewer samples than `buf.len()` have been recorded, only
+    /// those are returned.
+    #[doc(alias = "SDL_DequeueAudio")]
+    pub fn dequeue(&self, buf: &mut [Channel]) -> usize {
+        let len = u32::try_from(mem::size_of_val(buf)).unwrap_or(u32::MAX);
+        let bytes = unsafe {
+            sys::SDL_DequeueAudio(
+                self.queue.device_id.id(),
+                buf.as_mut_ptr() as *mut c_void,
+                len,
+            )
+        };
+        bytes as usize / mem::size_of::<Channel>()
+    }
+
+    /// Returns the number of recorded bytes waiting to be read with `dequeue`.
+    #[doc(alias = "SDL_GetQueuedAudioSize")]
+    pub fn size(&self) -> u32 {
+        self.queue.size()
+    }
+
+    /// Returns the number of recorded samples waiting to be read with `dequeue`.
+    #[doc(alias = "SDL_GetQueuedAudioSize")]
+    pub fn queued_size(&self) -> usize {
+        self.queue.queued_size()
+    }
 }
 
 /// Wraps `SDL_AudioDeviceID` and owns the callback data used by the audio device.
@@ -902,6 +1710,8 @@ impl<CB: AudioCallback> AudioDevice<CB> {
         spec: &AudioSpecDesired,
         get_callback: F,
         capture: bool,
+        format: AudioFormat,
+        allowed_changes: AllowedChanges,
     ) -> Result<AudioDevice<CB>, String>
     where
         F: FnOnce(AudioSpec) -> CB,
@@ -910,8 +1720,13 @@ impl<CB: AudioCallback> AudioDevice<CB> {
         use std::mem::MaybeUninit;
 
         let mut userdata: Box<Option<CB>> = Box::new(None);
-        let desired =
-            AudioSpecDesired::convert_to_ll(spec.freq, spec.channels, spec.samples, &mut *userdata);
+        let desired = AudioSpecDesired::convert_to_ll(
+            spec.freq,
+            format,
+            spec.channels,
+            spec.samples,
+            &mut *userdata,
+        );
 
         let mut obtained = MaybeUninit::uninit();
         unsafe {
@@ -927,7 +1742,7 @@ impl<CB: AudioCallback> AudioDevice<CB> {
                 iscapture_flag,
                 &desired,
                 obtained.as_mut_ptr(),
-                0,
+                allowed_changes.bits() as c_int,
             );
             match device_id {
                 0 => Err(get_error()),
@@ -963,7 +1778,13 @@ impl<CB: AudioCallback> AudioDevice<CB> {
         F: FnOnce(AudioSpec) -> CB,
         D: Into<Option<&'a str>>,
     {
-        AudioDevice::open(a, device, spec, get_callback, false)
+        AudioDevice::open_playback_with_changes(
+            a,
+            device,
+            spec,
+            AllowedChanges::empty(),
+            get_callback,
+        )
     }
 
     /// Opens a new audio device for capture (given the desired parameters and callback).
@@ -981,7 +1802,63 @@ impl<CB: AudioCallback> AudioDevice<CB> {
         F: FnOnce(AudioSpec) -> CB,
         D: Into<Option<&'a str>>,
     {
-        AudioDevice::open(a, device, spec, get_callback, true)
+        AudioDevice::open_capture_with_changes(
+            a,
+            device,
+            spec,
+            AllowedChanges::empty(),
+            get_callback,
+        )
+    }
+
+    /// Opens a new audio device for playback, allowing SDL to change the given
+    /// properties of the spec. `AllowedChanges::FORMAT` is ignored, the samples
+    /// always stay in the `CB::Channel` format.
+    pub fn open_playback_with_changes<'a, F, D>(
+        a: &AudioSubsystem,
+        device: D,
+        spec: &AudioSpecDesired,
+        allowed_changes: AllowedChanges,
+        get_callback: F,
+    ) -> Result<AudioDevice<CB>, String>
+    where
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        AudioDevice::open(
+            a,
+            device,
+            spec,
+            get_callback,
+            false,
+            CB::Channel::audio_format(),
+            allowed_changes - AllowedChanges::FORMAT,
+        )
+    }
+
+    /// Opens a new audio device for capture, allowing SDL to change the given
+    /// properties of the spec. `AllowedChanges::FORMAT` is ignored, the samples
+    /// always stay in the `CB::Channel` format.
+    pub fn open_capture_with_changes<'a, F, D>(
+        a: &AudioSubsystem,
+        device: D,
+        spec: &AudioSpecDesired,
+        allowed_changes: AllowedChanges,
+        get_callback: F,
+    ) -> Result<AudioDevice<CB>, String>
+    where
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        AudioDevice::open(
+            a,
+            device,
+            spec,
+            get_callback,
+            true,
+            CB::Channel::audio_format(),
+            allowed_changes - AllowedChanges::FORMAT,
+        )
     }
 
     #[inline]
@@ -1038,6 +1915,58 @@ impl<CB: AudioCallback> AudioDevice<CB> {
     }
 }
 
+impl<CB: DynamicAudioCallback> AudioDevice<DynamicAudio<CB>> {
+    /// Opens a new audio device for playback in the requested `format`, or in whichever
+    /// format the device prefers if `allowed_changes` contains `AllowedChanges::FORMAT`.
+    pub fn open_playback_dynamic<'a, F, D>(
+        a: &AudioSubsystem,
+        device: D,
+        spec: &AudioSpecDesired,
+        format: AudioFormat,
+        allowed_changes: AllowedChanges,
+        get_callback: F,
+    ) -> Result<AudioDevice<DynamicAudio<CB>>, String>
+    where
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        AudioDevice::open(
+            a,
+            device,
+            spec,
+            |spec| DynamicAudio::new(spec.format, get_callback(spec)),
+            false,
+            format,
+            allowed_changes,
+        )
+    }
+
+    /// Opens a new audio device for capture in the requested `format`, or in whichever
+    /// format the device prefers if `allowed_changes` contains `AllowedChanges::FORMAT`.
+    pub fn open_capture_dynamic<'a, F, D>(
+        a: &AudioSubsystem,
+        device: D,
+        spec: &AudioSpecDesired,
+        format: AudioFormat,
+        allowed_changes: AllowedChanges,
+        get_callback: F,
+    ) -> Result<AudioDevice<DynamicAudio<CB>>, String>
+    where
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        AudioDevice::open(
+            a,
+            device,
+            spec,
+            |spec| DynamicAudio::new(spec.format, get_callback(spec)),
+            true,
+            format,
+            allowed_changes,
+        )
+    }
+}
+
 /// Similar to `std::sync::MutexGuard`, but for use with `AudioDevice::lock()`.
 pub struct AudioDeviceLockGuard<'a, CB>
 where
@@ -1068,6 +1997,262 @@ impl<'a, CB: AudioCallback> Drop for AudioDeviceLockGuard<'a, CB> {
     }
 }
 
+/// A change reported by [`AudioDeviceManager::handle_event`](struct.AudioDeviceManager.html#method.handle_event).
+#[derive(Clone, PartialEq, Debug)]
+pub enum AudioDeviceChange {
+    /// The callback was moved to a newly opened device. `device` is `None` for the
+    /// system default device.
+    Reopened {
+        device: Option<String>,
+        spec: AudioSpec,
+    },
+    /// The device was removed and no other device could be opened. The callback is
+    /// kept and moved to the next device that is added.
+    Lost,
+}
+
+/// Keeps an `AudioCallback` playing (or recording) across audio device hot-plugging.
+///
+/// Pass every event to [`handle_event`](#method.handle_event). When the device in use
+/// is removed, the manager closes it, takes back the callback with
+/// `close_and_get_callback` and reopens it with the same `AudioSpecDesired`: on the
+/// preferred device if it is present, otherwise on the default device. When the
+/// preferred device is plugged back in, the callback moves back to it.
+///
+/// The callback keeps its state across devices, but the obtained spec may differ;
+/// check the returned [`AudioDeviceChange`](enum.AudioDeviceChange.html) or
+/// [`spec`](#method.spec).
+pub struct AudioDeviceManager<CB: AudioCallback> {
+    subsystem: AudioSubsystem,
+    desired: AudioSpecDesired,
+    capture: bool,
+    preferred: Option<String>,
+    /// The name of the open device, `None` for the default device.
+    current: Option<String>,
+    /// Exactly one of `device` and `closed_callback` is set.
+    device: Option<AudioDevice<CB>>,
+    closed_callback: Option<CB>,
+    playing: bool,
+}
+
+impl<CB: AudioCallback> AudioDeviceManager<CB> {
+    /// Opens a playback device managed for hot-plugging.
+    ///
+    /// `preferred` is tried first, falling back to the default device. The device
+    /// starts paused, like any other `AudioDevice`.
+    pub fn open_playback<'a, D>(
+        a: &AudioSubsystem,
+        preferred: D,
+        spec: &AudioSpecDesired,
+        callback: CB,
+    ) -> Result<AudioDeviceManager<CB>, String>
+    where
+        D: Into<Option<&'a str>>,
+    {
+        AudioDeviceManager::open(a, preferred.into(), spec, callback, false)
+    }
+
+    /// Opens a capture device managed for hot-plugging.
+    ///
+    /// See [`open_playback`](#method.open_playback).
+    pub fn open_capture<'a, D>(
+        a: &AudioSubsystem,
+        preferred: D,
+        spec: &AudioSpecDesired,
+        callback: CB,
+    ) -> Result<AudioDeviceManager<CB>, String>
+    where
+        D: Into<Option<&'a str>>,
+    {
+        AudioDeviceManager::open(a, preferred.into(), spec, callback, true)
+    }
+
+    fn open(
+        a: &AudioSubsystem,
+        preferred: Option<&str>,
+        spec: &AudioSpecDesired,
+        callback: CB,
+        capture: bool,
+    ) -> Result<AudioDeviceManager<CB>, String> {
+        let mut manager = AudioDeviceManager {
+            subsystem: a.clone(),
+            desired: spec.clone(),
+            capture,
+            preferred: preferred.map(|name| name.to_owned()),
+            current: None,
+            device: None,
+            closed_callback: Some(callback),
+            playing: false,
+        };
+        if manager.reopen(None) {
+            Ok(manager)
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Moves the closed callback to a newly opened device, trying `name`, then the
+    /// preferred device, then the default one. Returns false if none could be opened.
+    fn reopen(&mut self, name: Option<&str>) -> bool {
+        debug_assert!(self.device.is_none());
+
+        let preferred = self.preferred.clone();
+        let candidates = name
+            .map(|name| Some(name.to_owned()))
+            .into_iter()
+            .chain(preferred.map(Some))
+            .chain(Some(None));
+
+        for candidate in candidates {
+            let mut callback = self.closed_callback.take();
+            let result = AudioDevice::open(
+                &self.subsystem,
+                candidate.as_deref(),
+                &self.desired,
+                |_| callback.take().expect("Missing callback"),
+                self.capture,
+                CB::Channel::audio_format(),
+                AllowedChanges::empty(),
+            );
+            match result {
+                Ok(device) => {
+                    if self.playing {
+                        device.resume();
+                    }
+                    self.device = Some(device);
+                    self.current = candidate;
+                    return true;
+                }
+                Err(_) => self.closed_callback = callback,
+            }
+        }
+        false
+    }
+
+    fn reopened(&self) -> AudioDeviceChange {
+        AudioDeviceChange::Reopened {
+            device: self.current.clone(),
+            spec: *self.spec().expect("Device is open"),
+        }
+    }
+
+    /// Reacts to `AudioDeviceAdded` and `AudioDeviceRemoved` events, ignoring every
+    /// other event. Returns what changed, if anything.
+    pub fn handle_event(&mut self, event: &crate::event::Event) -> Option<AudioDeviceChange> {
+        use crate::event::Event;
+
+        match *event {
+            Event::AudioDeviceRemoved {
+                which, iscapture, ..
+            } if iscapture == self.capture && Some(which) == self.device_id() => {
+                let device = self.device.take().expect("Device is open");
+                self.closed_callback = Some(device.close_and_get_callback());
+                self.current = None;
+
+                if self.reopen(None) {
+                    Some(self.reopened())
+                } else {
+                    Some(AudioDeviceChange::Lost)
+                }
+            }
+            Event::AudioDeviceAdded {
+                which, iscapture, ..
+            } if iscapture == self.capture => {
+                let name = audio_device_name(which, self.capture).ok()?;
+                let is_preferred = self.preferred.as_ref() == Some(&name);
+
+                if self.device.is_some() {
+                    // Only move back to the preferred device.
+                    if !is_preferred || self.current.as_ref() == Some(&name) {
+                        return None;
+                    }
+                    let device = self.device.take().expect("Device is open");
+                    self.closed_callback = Some(device.close_and_get_callback());
+                }
+
+                if self.reopen(Some(&name)) {
+                    Some(self.reopened())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The instance ID of the open device, as reported in `Event::AudioDeviceRemoved`.
+    pub fn device_id(&self) -> Option<u32> {
+        self.device.as_ref().map(|device| device.device_id.id())
+    }
+
+    /// The name of the open device, `None` for the default device or when no device is open.
+    pub fn device_name(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.device.is_some()
+    }
+
+    /// The spec obtained from the open device.
+    pub fn spec(&self) -> Option<&AudioSpec> {
+        self.device.as_ref().map(|device| device.spec())
+    }
+
+    /// The status of the open device, `AudioStatus::Stopped` when no device is open.
+    pub fn status(&self) -> AudioStatus {
+        self.device
+            .as_ref()
+            .map_or(AudioStatus::Stopped, |device| device.status())
+    }
+
+    /// The device preferred over the default one, if any.
+    pub fn preferred_device(&self) -> Option<&str> {
+        self.preferred.as_deref()
+    }
+
+    /// Changes the preferred device. It is used the next time a device is added or removed.
+    pub fn set_preferred_device<'a, D: Into<Option<&'a str>>>(&mut self, preferred: D) {
+        self.preferred = preferred.into().map(|name| name.to_owned());
+    }
+
+    /// Pauses the device. Reopened devices stay paused.
+    #[doc(alias = "SDL_PauseAudioDevice")]
+    pub fn pause(&mut self) {
+        self.playing = false;
+        if let Some(device) = &self.device {
+            device.pause();
+        }
+    }
+
+    /// Starts the device. Reopened devices are started as well.
+    #[doc(alias = "SDL_PauseAudioDevice")]
+    pub fn resume(&mut self) {
+        self.playing = true;
+        if let Some(device) = &self.device {
+            device.resume();
+        }
+    }
+
+    /// Calls `f` with the callback, locking the device while it is open.
+    #[doc(alias = "SDL_LockAudioDevice")]
+    pub fn with_callback<R, F: FnOnce(&mut CB) -> R>(&mut self, f: F) -> R {
+        match self.device {
+            Some(ref mut device) => f(&mut device.lock()),
+            None => f(self.closed_callback.as_mut().expect("Missing callback")),
+        }
+    }
+
+    /// Closes the device, if open, and returns the callback.
+    pub fn into_callback(self) -> CB {
+        match self.device {
+            Some(device) => device.close_and_get_callback(),
+            None => self.closed_callback.expect("Missing callback"),
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct AudioCVT {
     raw: sys::SDL_AudioCVT,
@@ -1170,9 +2355,580 @@ impl AudioCVT {
     }
 }
 
+/// A typed wrapper around `SDL_AudioStream`, converting `In` samples into `Out` samples.
+///
+/// Unlike `AudioCVT`, an audio stream can resample between arbitrary rates and
+/// accepts its input piecewise: push data with [`put`](#method.put) as it becomes
+/// available and pull converted data out with [`get`](#method.get).
+/// Supported since SDL 2.0.7
+pub struct AudioStream<In: AudioFormatNum, Out: AudioFormatNum> {
+    raw: *mut sys::SDL_AudioStream,
+    _marker: PhantomData<(In, Out)>,
+}
+
+impl<In: AudioFormatNum, Out: AudioFormatNum> AudioStream<In, Out> {
+    /// Creates a new stream converting from `src_channels` channels at `src_rate` Hz
+    /// to `dst_channels` channels at `dst_rate` Hz.
+    #[doc(alias = "SDL_NewAudioStream")]
+    pub fn new(
+        src_channels: u8,
+        src_rate: i32,
+        dst_channels: u8,
+        dst_rate: i32,
+    ) -> Result<AudioStream<In, Out>, String> {
+        let raw = unsafe {
+            sys::SDL_NewAudioStream(
+                In::audio_format().to_ll(),
+                src_channels,
+                src_rate as c_int,
+                Out::audio_format().to_ll(),
+                dst_channels,
+                dst_rate as c_int,
+            )
+        };
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            Ok(AudioStream {
+                raw,
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    /// Adds interleaved samples to the stream.
+    #[doc(alias = "SDL_AudioStreamPut")]
+    pub fn put(&mut self, data: &[In]) -> Result<(), String> {
+        let len = c_int::try_from(mem::size_of_val(data))
+            .map_err(|_| "Buffer length overflow".to_owned())?;
+        let result =
+            unsafe { sys::SDL_AudioStreamPut(self.raw, data.as_ptr() as *const c_void, len) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Reads converted samples into `buf`, returning the number of samples written.
+    #[doc(alias = "SDL_AudioStreamGet")]
+    pub fn get(&mut self, buf: &mut [Out]) -> Result<usize, String> {
+        let len = c_int::try_from(mem::size_of_val(buf)).unwrap_or(c_int::MAX);
+        let result =
+            unsafe { sys::SDL_AudioStreamGet(self.raw, buf.as_mut_ptr() as *mut c_void, len) };
+        if result < 0 {
+            Err(get_error())
+        } else {
+            Ok(result as usize / mem::size_of::<Out>())
+        }
+    }
+
+    /// Returns the number of converted samples ready to be read with `get`.
+    #[doc(alias = "SDL_AudioStreamAvailable")]
+    pub fn available(&self) -> usize {
+        let bytes = unsafe { sys::SDL_AudioStreamAvailable(self.raw) };
+        bytes.max(0) as usize / mem::size_of::<Out>()
+    }
+
+    /// Converts any data still buffered in the stream so it becomes available
+    /// to `get`. Call this once the input has ended.
+    #[doc(alias = "SDL_AudioStreamFlush")]
+    pub fn flush(&mut self) -> Result<(), String> {
+        let result = unsafe { sys::SDL_AudioStreamFlush(self.raw) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Discards all pending data in the stream without converting it.
+    #[doc(alias = "SDL_AudioStreamClear")]
+    pub fn clear(&mut self) {
+        unsafe { sys::SDL_AudioStreamClear(self.raw) }
+    }
+}
+
+impl<In: AudioFormatNum, Out: AudioFormatNum> Drop for AudioStream<In, Out> {
+    #[doc(alias = "SDL_FreeAudioStream")]
+    fn drop(&mut self) {
+        unsafe { sys::SDL_FreeAudioStream(self.raw) }
+    }
+}
+
+/// The `volume` passed to [`mix_into`](fn.mix_into.html) that leaves the source unchanged.
+pub const MIX_MAXVOLUME: u8 = sys::SDL_MIX_MAXVOLUME as u8;
+
+/// Mixes `src` into `dst` at the given volume (0 to `MIX_MAXVOLUME`), clipping on overflow.
+///
+/// Only the first `min(dst.len(), src.len())` samples are mixed. Mixing more than two
+/// streams this way may distort, as every call clips; use a [`Mixer`](struct.Mixer.html)
+/// or [`mix_with_gain`](fn.mix_with_gain.html) for that.
+#[doc(alias = "SDL_MixAudioFormat")]
+pub fn mix_into<T: AudioFormatNum>(dst: &mut [T], src: &[T], volume: u8) {
+    let len = dst.len().min(src.len());
+    let bytes = u32::try_from(len * mem::size_of::<T>()).expect("Buffer length overflow");
+    unsafe {
+        sys::SDL_MixAudioFormat(
+            dst.as_mut_ptr() as *mut u8,
+            src.as_ptr() as *const u8,
+            T::audio_format().to_ll(),
+            bytes,
+            volume.min(MIX_MAXVOLUME) as c_int,
+        )
+    }
+}
+
+/// Sample types that can be mixed in Rust, without going through SDL.
+pub trait MixSample: AudioFormatNum + Copy {
+    /// Converts the sample to the `-1.0..=1.0` range.
+    fn to_f32(self) -> f32;
+    /// Converts a sample in the `-1.0..=1.0` range back, clamping anything outside of it.
+    fn from_f32(sample: f32) -> Self;
+}
+
+impl MixSample for f32 {
+    #[inline]
+    fn to_f32(self) -> f32 {
+        self
+    }
+    #[inline]
+    fn from_f32(sample: f32) -> f32 {
+        sample.clamp(-1.0, 1.0)
+    }
+}
+
+impl MixSample for i16 {
+    #[inline]
+    fn to_f32(self) -> f32 {
+        self as f32 / 32768.0
+    }
+    #[inline]
+    fn from_f32(sample: f32) -> i16 {
+        (sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16
+    }
+}
+
+/// Mixes `src` scaled by `gain` into `dst`, clamping the result.
+///
+/// A pure-Rust counterpart to [`mix_into`](fn.mix_into.html) that accepts any gain.
+/// Only the first `min(dst.len(), src.len())` samples are mixed.
+pub fn mix_with_gain<T: MixSample>(dst: &mut [T], src: &[T], gain: f32) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = T::from_f32(d.to_f32() + s.to_f32() * gain);
+    }
+}
+
+/// Identifies a voice playing in a [`Mixer`](struct.Mixer.html).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct VoiceId(u32);
+
+struct Voice<T> {
+    id: VoiceId,
+    samples: Arc<[T]>,
+    position: usize,
+    looping: bool,
+    gain: f32,
+    pan: f32,
+}
+
+impl<T> Voice<T> {
+    /// Equal-power gains for the left and right channel.
+    fn stereo_gains(&self) -> (f32, f32) {
+        let angle = (self.pan + 1.0) * std::f32::consts::FRAC_PI_4;
+        (self.gain * angle.cos(), self.gain * angle.sin())
+    }
+}
+
+/// Mixes any number of mono voices into an output with any number of channels, each with
+/// its own gain and pan.
+///
+/// With two channels or more, voices are panned between the first two channels, which are
+/// the front left and right speakers in all of SDL's channel layouts. The other channels
+/// (center, LFE, surround) are left silent.
+///
+/// `Mixer` implements `AudioCallback`, so it can be handed directly to
+/// `AudioSubsystem::open_playback` and controlled through `AudioDevice::lock`:
+///
+/// ```no_run
+/// use sdl2::audio::{AudioSpecDesired, Mixer};
+///
+/// let sdl_context = sdl2::init().unwrap();
+/// let audio_subsystem = sdl_context.audio().unwrap();
+/// let desired_spec = AudioSpecDesired { freq: Some(44100), channels: Some(2), samples: None };
+///
+/// let mut device = audio_subsystem
+///     .open_playback(None, &desired_spec, |spec| Mixer::<f32>::new(spec.channels))
+///     .unwrap();
+///
+/// let beep: Vec<f32> = (0..44100).map(|i| (i as f32 * 0.06).sin() * 0.5).collect();
+/// let voice = device.lock().play(beep, false);
+/// device.lock().set_pan(voice, -0.5);
+/// device.resume();
+/// ```
+pub struct Mixer<T: MixSample> {
+    channels: u8,
+    voices: Vec<Voice<T>>,
+    next_id: u32,
+    accumulator: Vec<f32>,
+}
+
+impl<T: MixSample> Mixer<T> {
+    /// Creates a mixer for an output with `channels` interleaved channels.
+    pub fn new(channels: u8) -> Mixer<T> {
+        Mixer {
+            channels,
+            voices: Vec::new(),
+            next_id: 0,
+            accumulator: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn channels(&self) -> u8 {
+        self.channels
+    }
+
+    /// Starts playing the mono `samples` at full gain, centered.
+    ///
+    /// Unless `looping` is set, the voice is removed once it has played to the end.
+    pub fn play<S: Into<Arc<[T]>>>(&mut self, samples: S, looping: bool) -> VoiceId {
+        let id = VoiceId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        self.voices.push(Voice {
+            id,
+            samples: samples.into(),
+            position: 0,
+            looping,
+            gain: 1.0,
+            pan: 0.0,
+        });
+        id
+    }
+
+    fn voice_mut(&mut self, id: VoiceId) -> Option<&mut Voice<T>> {
+        self.voices.iter_mut().find(|voice| voice.id == id)
+    }
+
+    /// Sets the gain of a voice, where 1.0 leaves it unchanged.
+    /// Returns false if the voice is no longer playing.
+    pub fn set_gain(&mut self, id: VoiceId, gain: f32) -> bool {
+        self.voice_mut(id).map(|voice| voice.gain = gain).is_some()
+    }
+
+    /// Sets the pan of a voice, from -1.0 (left) through 0.0 (center) to 1.0 (right).
+    /// Ignored for mono output; see [`Mixer`] for outputs with more than two channels. Returns false if the voice is no longer playing.
+    pub fn set_pan(&mut self, id: VoiceId, pan: f32) -> bool {
+        self.voice_mut(id)
+            .map(|voice| voice.pan = pan.clamp(-1.0, 1.0))
+            .is_some()
+    }
+
+    /// Stops a voice. Returns false if it was no longer playing.
+    pub fn stop(&mut self, id: VoiceId) -> bool {
+        let len = self.voices.len();
+        self.voices.retain(|voice| voice.id != id);
+        self.voices.len() != len
+    }
+
+    /// Stops all voices.
+    pub fn stop_all(&mut self) {
+        self.voices.clear();
+    }
+
+    pub fn is_playing(&self, id: VoiceId) -> bool {
+        self.voices.iter().any(|voice| voice.id == id)
+    }
+
+    /// The number of voices currently playing.
+    pub fn voice_count(&self) -> usize {
+        self.voices.len()
+    }
+
+    /// Overwrites `out` with the next interleaved frames of all voices mixed together.
+    ///
+    /// Voices are summed before clamping, so any number of them can be mixed without
+    /// the distortion repeated `mix_into` calls would cause.
+    pub fn mix(&mut self, out: &mut [T]) {
+        let channels = self.channels as usize;
+        // A mixer without channels has no frames to fill, and only outputs silence.
+        let frames = out.len().checked_div(channels).unwrap_or(0);
+
+        self.accumulator.clear();
+        self.accumulator.resize(out.len(), 0.0);
+
+        for voice in self.voices.iter_mut() {
+            let (left, right) = voice.stereo_gains();
+            let mut frame = 0;
+            while frame < frames && !voice.samples.is_empty() {
+                if voice.position == voice.samples.len() {
+                    if !voice.looping {
+                        break;
+                    }
+                    voice.position = 0;
+                }
+                let count = (frames - frame).min(voice.samples.len() - voice.position);
+                let src = &voice.samples[voice.position..voice.position + count];
+                let dst = &mut self.accumulator[frame * channels..(frame + count) * channels];
+                if channels == 1 {
+                    for (d, s) in dst.iter_mut().zip(src) {
+                        *d += s.to_f32() * voice.gain;
+                    }
+                } else {
+                    for (d, s) in dst.chunks_exact_mut(channels).zip(src) {
+                        let s = s.to_f32();
+                        d[0] += s * left;
+                        d[1] += s * right;
+                    }
+                }
+                voice.position += count;
+                frame += count;
+            }
+        }
+
+        self.voices
+            .retain(|voice| voice.looping || voice.position < voice.samples.len());
+
+        for (o, a) in out.iter_mut().zip(&self.accumulator) {
+            *o = T::from_f32(*a);
+        }
+    }
+}
+
+impl<T: MixSample + Send + Sync + 'static> AudioCallback for Mixer<T> {
+    type Channel = T;
+
+    fn callback(&mut self, out: &mut [T]) {
+        self.mix(out);
+    }
+}
+
+struct RingBufferShared<T> {
+    buffer: Box<[UnsafeCell<T>]>,
+    /// Total number of samples ever read; only written by the consumer.
+    head: AtomicUsize,
+    /// Total number of samples ever written; only written by the producer.
+    tail: AtomicUsize,
+    underruns: AtomicUsize,
+    overruns: AtomicUsize,
+}
+
+// Each slot is only ever accessed by one side at a time, as handed over through `head` and `tail`.
+unsafe impl<T: Send> Sync for RingBufferShared<T> {}
+
+impl<T> RingBufferShared<T> {
+    #[inline]
+    fn slot(&self, position: usize) -> *mut T {
+        // The capacity is a power of two, so this stays correct when the positions wrap.
+        self.buffer[position & (self.buffer.len() - 1)].get()
+    }
+
+    fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+}
+
+/// A lock-free single-producer, single-consumer queue of samples, for feeding audio
+/// generated on another thread into an audio callback without `AudioDevice::lock`.
+///
+/// [`split`](#method.split) it into a [`RingBufferProducer`](struct.RingBufferProducer.html)
+/// for the game thread and a [`RingBufferConsumer`](struct.RingBufferConsumer.html),
+/// which implements `AudioCallback`:
+///
+/// ```no_run
+/// use sdl2::audio::{AudioSpecDesired, RingBuffer};
+///
+/// let sdl_context = sdl2::init().unwrap();
+/// let audio_subsystem = sdl_context.audio().unwrap();
+/// let desired_spec = AudioSpecDesired { freq: Some(48000), channels: Some(2), samples: None };
+///
+/// let (mut producer, consumer) = RingBuffer::<f32>::new(48000).split();
+/// let device = audio_subsystem
+///     .open_playback(None, &desired_spec, |_| consumer)
+///     .unwrap();
+/// device.resume();
+///
+/// loop {
+///     let samples = vec![0.0; producer.free_len()];
+///     producer.push(&samples);
+///     # break;
+/// }
+/// ```
+pub struct RingBuffer<T: AudioFormatNum + Copy + Send> {
+    shared: Arc<RingBufferShared<T>>,
+}
+
+impl<T: AudioFormatNum + Copy + Send> RingBuffer<T> {
+    /// Creates a ring buffer holding at least `capacity` samples, filled with silence.
+    /// The capacity is rounded up to a power of two.
+    pub fn new(capacity: usize) -> RingBuffer<T> {
+        let capacity = capacity.max(1).next_power_of_two();
+        RingBuffer {
+            shared: Arc::new(RingBufferShared {
+                buffer: (0..capacity).map(|_| UnsafeCell::new(T::SILENCE)).collect(),
+                head: AtomicUsize::new(0),
+                tail: AtomicUsize::new(0),
+                underruns: AtomicUsize::new(0),
+                overruns: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.buffer.len()
+    }
+
+    /// Splits the ring buffer into its writing and reading halves.
+    pub fn split(self) -> (RingBufferProducer<T>, RingBufferConsumer<T>) {
+        (
+            RingBufferProducer {
+                shared: self.shared.clone(),
+            },
+            RingBufferConsumer {
+                shared: self.shared,
+            },
+        )
+    }
+}
+
+/// The writing half of a [`RingBuffer`](struct.RingBuffer.html).
+pub struct RingBufferProducer<T: AudioFormatNum + Copy + Send> {
+    shared: Arc<RingBufferShared<T>>,
+}
+
+impl<T: AudioFormatNum + Copy + Send> RingBufferProducer<T> {
+    /// Appends as many of `samples` as fit, returning how many were written.
+    ///
+    /// Samples that do not fit are dropped and counted as overruns.
+    pub fn push(&mut self, samples: &[T]) -> usize {
+        let shared = &*self.shared;
+        let tail = shared.tail.load(Ordering::Relaxed);
+        let head = shared.head.load(Ordering::Acquire);
+        let free = shared.buffer.len() - tail.wrapping_sub(head);
+        let count = samples.len().min(free);
+
+        for (i, &sample) in samples[..count].iter().enumerate() {
+            unsafe { *shared.slot(tail.wrapping_add(i)) = sample };
+        }
+        shared
+            .tail
+            .store(tail.wrapping_add(count), Ordering::Release);
+
+        if count < samples.len() {
+            shared
+                .overruns
+                .fetch_add(samples.len() - count, Ordering::Relaxed);
+        }
+        count
+    }
+
+    /// The number of samples that can be pushed without overrunning.
+    pub fn free_len(&self) -> usize {
+        self.shared.buffer.len() - self.shared.len()
+    }
+
+    /// The number of samples waiting to be consumed.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.buffer.len()
+    }
+
+    /// The number of silent samples the consumer had to output because the buffer ran dry.
+    pub fn underruns(&self) -> usize {
+        self.shared.underruns.load(Ordering::Relaxed)
+    }
+
+    /// The number of samples dropped by `push` because the buffer was full.
+    pub fn overruns(&self) -> usize {
+        self.shared.overruns.load(Ordering::Relaxed)
+    }
+}
+
+/// The reading half of a [`RingBuffer`](struct.RingBuffer.html).
+///
+/// As an `AudioCallback` it plays (or records into) the buffered samples, filling
+/// any shortfall with `T::SILENCE`. It never blocks.
+pub struct RingBufferConsumer<T: AudioFormatNum + Copy + Send> {
+    shared: Arc<RingBufferShared<T>>,
+}
+
+impl<T: AudioFormatNum + Copy + Send> RingBufferConsumer<T> {
+    /// Moves up to `out.len()` samples into `out`, returning how many were read.
+    pub fn pop(&mut self, out: &mut [T]) -> usize {
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Relaxed);
+        let tail = shared.tail.load(Ordering::Acquire);
+        let count = out.len().min(tail.wrapping_sub(head));
+
+        for (i, sample) in out[..count].iter_mut().enumerate() {
+            *sample = unsafe { *shared.slot(head.wrapping_add(i)) };
+        }
+        shared
+            .head
+            .store(head.wrapping_add(count), Ordering::Release);
+        count
+    }
+
+    /// The number of samples waiting to be consumed.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.buffer.len()
+    }
+
+    /// The number of silent samples output because the buffer ran dry.
+    pub fn underruns(&self) -> usize {
+        self.shared.underruns.load(Ordering::Relaxed)
+    }
+
+    /// The number of samples dropped by the producer because the buffer was full.
+    pub fn overruns(&self) -> usize {
+        self.shared.overruns.load(Ordering::Relaxed)
+    }
+}
+
+impl<T: AudioFormatNum + Copy + Send + 'static> AudioCallback for RingBufferConsumer<T> {
+    type Channel = T;
+
+    fn callback(&mut self, out: &mut [T]) {
+        let read = self.pop(out);
+        if read < out.len() {
+            for sample in out[read..].iter_mut() {
+                *sample = T::SILENCE;
+            }
+            self.shared
+                .underruns
+                .fetch_add(out.len() - read, Ordering::Relaxed);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::{AudioCVT, AudioFormat};
+    use super::{
+        deinterleave, frames, frames_mut, interleave, mix_into, mix_with_gain, AudioCVT,
+        AudioCallback, AudioFormat, AudioSpecWAV, AudioStream, ChannelLayout, Mixer, RingBuffer,
+        Speaker, WavWriter, MIX_MAXVOLUME,
+    };
+    use crate::rwops::RWops;
 
     #[test]
     fn test_audio_cvt() {
@@ -1204,4 +2960,237 @@ mod test {
         // // to maybe re-enable it someday
         // assert_eq!(new_buffer, new_buffer_expected);
     }
+
+    #[test]
+    fn test_audio_stream() {
+        let ramp: Vec<i16> = (0..4410).map(|i| (i * 7) as i16).collect();
+
+        let mut stream = AudioStream::<i16, f32>::new(1, 44100, 1, 48000).unwrap();
+        stream.put(&ramp).unwrap();
+        stream.flush().unwrap();
+
+        let available = stream.available();
+        let mut out = vec![0.0f32; available + 16];
+        let read = stream.get(&mut out).unwrap();
+        assert_eq!(read, available);
+        assert_eq!(stream.available(), 0);
+
+        // 100ms of input resampled to 48kHz, give or take the resampler's edges.
+        assert!((read as i32 - 4800).abs() < 64, "got {} samples", read);
+        assert!(out[..read].iter().all(|s| (-1.0..=1.0).contains(s)));
+
+        stream.put(&ramp).unwrap();
+        stream.clear();
+        stream.flush().unwrap();
+        assert_eq!(stream.available(), 0);
+    }
+
+    #[test]
+    fn test_mix_into() {
+        let mut dst = [1000i16, -1000, 30000, -30000];
+        mix_into(&mut dst, &[1000, 1000, 10000, -10000], MIX_MAXVOLUME);
+        assert_eq!(dst, [2000, 0, 32767, -32768]);
+
+        let mut dst = [0i16; 2];
+        mix_into(&mut dst, &[1000, 1000, 1000], 0);
+        assert_eq!(dst, [0, 0]);
+    }
+
+    #[test]
+    fn test_mix_with_gain() {
+        let mut dst = [0.5f32, -0.5, 0.0];
+        mix_with_gain(&mut dst, &[0.75, -0.75, 0.5], 1.0);
+        assert_eq!(dst, [1.0, -1.0, 0.5]);
+
+        let mut dst = [16384i16, -16384, 0];
+        mix_with_gain(&mut dst, &[32767, -32768, 8192], 2.0);
+        assert_eq!(dst, [32767, -32768, 16384]);
+    }
+
+    #[test]
+    fn test_mixer() {
+        let mut mixer = Mixer::<f32>::new(2);
+        let short = mixer.play(vec![0.5f32; 3], false);
+        let looped = mixer.play(vec![0.25f32, -0.25], true);
+        assert!(mixer.set_pan(short, -1.0));
+        assert!(mixer.set_gain(looped, 2.0));
+        assert!(mixer.set_pan(looped, 1.0));
+
+        let mut out = [0.0f32; 8];
+        mixer.mix(&mut out);
+        // The short voice only reaches the left channel, the looped one the right.
+        let left: Vec<f32> = out
+            .iter()
+            .step_by(2)
+            .map(|s| (s * 1000.0).round())
+            .collect();
+        let right: Vec<f32> = out
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .map(|s| (s * 1000.0).round())
+            .collect();
+        assert_eq!(left, [500.0, 500.0, 500.0, 0.0]);
+        assert_eq!(right, [500.0, -500.0, 500.0, -500.0]);
+
+        assert!(!mixer.is_playing(short));
+        assert!(!mixer.set_gain(short, 1.0));
+        assert_eq!(mixer.voice_count(), 1);
+        assert!(mixer.stop(looped));
+
+        mixer.mix(&mut out);
+        assert!(out.iter().all(|&s| s == 0.0));
+
+        // With 5.1 output, voices only reach the front left and right channels.
+        let mut mixer = Mixer::<i16>::new(6);
+        let voice = mixer.play(vec![16384i16; 2], false);
+        assert!(mixer.set_pan(voice, -1.0));
+        let mut out = [1i16; 14];
+        mixer.mix(&mut out);
+        assert_eq!(out, [16384, 0, 0, 0, 0, 0, 16384, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_wav_writer() {
+        use std::io::Cursor;
+
+        let samples: Vec<i16> = (0..1000).map(|i| (i * 31 - 15000) as i16).collect();
+        let mut writer = WavWriter::<_, i16>::new(Cursor::new(Vec::new()), 22050, 2).unwrap();
+        writer.write_samples(&samples[..600]).unwrap();
+        writer.flush().unwrap();
+        writer.write_samples(&samples[600..]).unwrap();
+        assert_eq!(writer.data_len(), 2000);
+        let bytes = writer.finalize().unwrap().into_inner();
+        assert_eq!(bytes.len(), 44 + 2000);
+
+        let wav = AudioSpecWAV::load_wav_rw(&mut RWops::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(wav.freq, 22050);
+        assert_eq!(wav.channels, 2);
+        assert_eq!(wav.format, AudioFormat::s16_sys());
+        assert_eq!(wav.samples::<i16>(), Some(&samples[..]));
+        assert_eq!(wav.samples::<f32>(), None);
+    }
+
+    #[test]
+    fn test_wav_writer_float() {
+        use std::io::Cursor;
+
+        let samples = [0.0f32, 0.5, -0.5, 1.0];
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            // Dropping the writer finishes the header as well.
+            let mut writer = WavWriter::<_, f32>::new(&mut buffer, 48000, 1).unwrap();
+            writer.write_samples(&samples).unwrap();
+        }
+
+        let bytes = buffer.into_inner();
+        let wav = AudioSpecWAV::load_wav_rw(&mut RWops::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(wav.format, AudioFormat::f32_sys());
+        assert_eq!(wav.samples::<f32>(), Some(&samples[..]));
+
+        assert!(WavWriter::<_, i8>::new(Cursor::new(Vec::new()), 48000, 1).is_err());
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let ring = RingBuffer::<i16>::new(6);
+        assert_eq!(ring.capacity(), 8);
+        let (mut producer, mut consumer) = ring.split();
+
+        assert_eq!(producer.push(&[1, 2, 3, 4, 5]), 5);
+        assert_eq!(producer.push(&[6, 7, 8, 9, 10]), 3);
+        assert_eq!(producer.overruns(), 2);
+        assert_eq!(producer.free_len(), 0);
+
+        let mut out = [0i16; 6];
+        assert_eq!(consumer.pop(&mut out), 6);
+        assert_eq!(out, [1, 2, 3, 4, 5, 6]);
+
+        // Wrap around the end of the storage.
+        assert_eq!(producer.push(&[11, 12, 13]), 3);
+        let mut out = [-1i16; 8];
+        consumer.callback(&mut out);
+        assert_eq!(out, [7, 8, 11, 12, 13, 0, 0, 0]);
+        assert_eq!(consumer.underruns(), 3);
+        assert!(consumer.is_empty());
+
+        let (mut producer, mut consumer) = RingBuffer::<u8>::new(4).split();
+        producer.push(&[1]);
+        let mut out = [0u8; 3];
+        consumer.callback(&mut out);
+        assert_eq!(out, [1, 0x80, 0x80]);
+    }
+
+    #[test]
+    fn test_ring_buffer_threads() {
+        let (mut producer, mut consumer) = RingBuffer::<i32>::new(64).split();
+
+        let writer = std::thread::spawn(move || {
+            let mut next = 0;
+            while next < 10_000 {
+                let chunk: Vec<i32> = (next..(next + 37).min(10_000)).collect();
+                next += producer.push(&chunk) as i32;
+            }
+        });
+
+        let mut expected = 0;
+        let mut out = [0i32; 29];
+        while expected < 10_000 {
+            let read = consumer.pop(&mut out);
+            for &sample in &out[..read] {
+                assert_eq!(sample, expected);
+                expected += 1;
+            }
+        }
+        writer.join().unwrap();
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn test_channel_layout() {
+        for channels in 0..=8 {
+            if let Some(layout) = ChannelLayout::from_channels(channels) {
+                assert_eq!(layout.channels(), channels);
+            }
+        }
+        assert_eq!(ChannelLayout::from_channels(3), None);
+        assert_eq!(ChannelLayout::Stereo.index_of(Speaker::FrontRight), Some(1));
+        assert_eq!(ChannelLayout::Quad.index_of(Speaker::FrontCenter), None);
+        assert_eq!(
+            ChannelLayout::Surround51.index_of(Speaker::LowFrequency),
+            Some(3)
+        );
+        assert_eq!(
+            ChannelLayout::Surround71.index_of(Speaker::SideRight),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut buf = [0i16; 7];
+        for (i, frame) in frames_mut::<_, 2>(&mut buf).enumerate() {
+            *frame = [i as i16, -(i as i16)];
+        }
+        assert_eq!(buf, [0, 0, 1, -1, 2, -2, 0]);
+        assert_eq!(frames::<_, 3>(&buf).count(), 2);
+        assert_eq!(frames::<_, 3>(&buf).nth(1), Some(&[-1, 2, -2]));
+    }
+
+    #[test]
+    fn test_interleave() {
+        let left = [1.0f32, 2.0, 3.0];
+        let right = [-1.0f32, -2.0];
+        let mut out = [0.0f32; 6];
+        assert_eq!(interleave(&[&left[..], &right[..]], &mut out), 2);
+        assert_eq!(out, [1.0, -1.0, 2.0, -2.0, 0.0, 0.0]);
+
+        let mut left = [0.0f32; 3];
+        let mut right = [0.0f32; 3];
+        assert_eq!(deinterleave(&out, &mut [&mut left, &mut right]), 3);
+        assert_eq!(left, [1.0, 2.0, 0.0]);
+        assert_eq!(right, [-1.0, -2.0, 0.0]);
+
+        assert_eq!(interleave::<f32>(&[], &mut out), 0);
+    }
 }
@@ -2,15 +2,24 @@
 
 use std::borrow::ToOwned;
 use std::collections::HashMap;
-use std::convert::TryFrom;
-use std::ffi::CStr;
+#[cfg(feature = "futures")]
+use std::collections::VecDeque;
+use std::convert::{TryFrom, TryInto};
+use std::ffi::{CStr, CString};
+use std::io;
+use std::io::Read;
 use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::mem;
 use std::mem::transmute;
+use std::ops::RangeInclusive;
+use std::pin::Pin;
 use std::ptr;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
 
+use libc::c_char;
 use libc::c_int;
 use libc::c_void;
 
@@ -30,9 +39,60 @@ use crate::sys::SDL_EventFilter;
 use crate::sys::SDL_EventType;
 use crate::video::Orientation;
 
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+
+/// A boxed custom event payload that has been pushed but not taken yet.
+///
+/// The payload is owned by this registry entry until it is either taken with
+/// `Event::take_user_event` or freed while flushing the event queue, so an
+/// `Event::User` that has been copied or cloned can never free it twice.
+struct CustomEventPayload {
+    sdl_id: u32,
+    /// The address of the boxed payload, also stored in `data1` of the event.
+    ptr: usize,
+    drop_fn: unsafe fn(*mut c_void),
+    /// Called with `true` once the event is removed from the queue by the application or its
+    /// payload is taken, or with `false` if the payload is freed before that.
+    on_received: Option<Box<dyn FnOnce(bool) + Send>>,
+}
+
+unsafe fn drop_custom_event_payload<T>(ptr: *mut c_void) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+impl CustomEventPayload {
+    /// Takes ownership of the payload, which must be of type `T`.
+    ///
+    /// Must be called without holding the `CUSTOM_EVENT_TYPES` lock.
+    unsafe fn into_inner<T>(self) -> T {
+        let event_box: Box<T> = Box::from_raw(self.ptr as *mut T);
+        if let Some(on_received) = self.on_received {
+            on_received(true);
+        }
+        *event_box
+    }
+
+    /// Frees the payload.
+    ///
+    /// Must be called without holding the `CUSTOM_EVENT_TYPES` lock, as the `Drop` of the
+    /// payload may push or flush events itself.
+    fn free(self) {
+        unsafe { (self.drop_fn)(self.ptr as *mut c_void) };
+        if let Some(on_received) = self.on_received {
+            on_received(false);
+        }
+    }
+}
+
 struct CustomEventTypeMaps {
     sdl_id_to_type_id: HashMap<u32, ::std::any::TypeId>,
     type_id_to_sdl_id: HashMap<::std::any::TypeId, u32>,
+    /// Pending payloads, keyed by the token stored in `data2` of their event.
+    payloads: HashMap<usize, CustomEventPayload>,
+    /// The token of the next pushed payload. Tokens are never reused, unlike the addresses
+    /// of freed (or zero-sized) payloads.
+    next_token: usize,
 }
 
 impl CustomEventTypeMaps {
@@ -40,6 +100,44 @@ impl CustomEventTypeMaps {
         CustomEventTypeMaps {
             sdl_id_to_type_id: HashMap::new(),
             type_id_to_sdl_id: HashMap::new(),
+            payloads: HashMap::new(),
+            next_token: 1,
+        }
+    }
+
+    /// Removes the payload of the event `sdl_id` with the given token, if it is still pending
+    /// and of type `T`.
+    fn take_payload<T: ::std::any::Any>(
+        &mut self,
+        sdl_id: u32,
+        token: usize,
+    ) -> Option<CustomEventPayload> {
+        use std::any::TypeId;
+
+        if self.sdl_id_to_type_id.get(&sdl_id) != Some(&TypeId::of::<Box<T>>()) {
+            return None;
+        }
+        self.remove_payload(sdl_id, token)
+    }
+
+    /// Takes the receipt callback of the payload of the event `sdl_id` with the given token, if
+    /// it is still pending and has not been notified yet.
+    fn take_on_received(
+        &mut self,
+        sdl_id: u32,
+        token: usize,
+    ) -> Option<Box<dyn FnOnce(bool) + Send>> {
+        match self.payloads.get_mut(&token) {
+            Some(payload) if payload.sdl_id == sdl_id => payload.on_received.take(),
+            _ => None,
+        }
+    }
+
+    /// Removes the payload of the event `sdl_id` with the given token, if it is still pending.
+    fn remove_payload(&mut self, sdl_id: u32, token: usize) -> Option<CustomEventPayload> {
+        match self.payloads.get(&token) {
+            Some(payload) if payload.sdl_id == sdl_id => self.payloads.remove(&token),
+            _ => None,
         }
     }
 }
@@ -49,17 +147,65 @@ lazy_static! {
         Mutex::new(CustomEventTypeMaps::new());
 }
 
+/// Removes the queued user events in the given type range, freeing the payloads of the custom
+/// events among them.
+unsafe fn flush_custom_events(min_type: u32, max_type: u32) {
+    let min_type = min_type.max(SDL_EventType::SDL_USEREVENT as u32);
+    let max_type = max_type.min(SDL_EventType::SDL_LASTEVENT as u32);
+    if min_type > max_type {
+        return;
+    }
+
+    const BATCH_SIZE: usize = 64;
+    let mut events: Vec<sys::SDL_Event> = Vec::with_capacity(BATCH_SIZE);
+    loop {
+        let result = sys::SDL_PeepEvents(
+            events.as_mut_ptr(),
+            BATCH_SIZE as c_int,
+            sys::SDL_eventaction::SDL_GETEVENT,
+            min_type,
+            max_type,
+        );
+        if result <= 0 {
+            break;
+        }
+        events.set_len(result as usize);
+
+        let payloads: Vec<_> = {
+            let mut cet = CUSTOM_EVENT_TYPES.lock().unwrap();
+            events
+                .drain(..)
+                .filter_map(|event| cet.remove_payload(event.user.type_, event.user.data2 as usize))
+                .collect()
+        };
+        for payload in payloads {
+            payload.free();
+        }
+    }
+}
+
+/// The capacity of SDL's event queue (`SDL_MAX_QUEUED_EVENTS`). No call can read or add more
+/// events, so larger amounts are clamped to it before allocating.
+const MAX_QUEUED_EVENTS: u32 = 65535;
+
 impl crate::EventSubsystem {
     /// Removes all events in the event queue that match the specified event type.
+    ///
+    /// The payloads of flushed custom events are freed.
     #[doc(alias = "SDL_FlushEvent")]
     pub fn flush_event(&self, event_type: EventType) {
-        unsafe { sys::SDL_FlushEvent(event_type as u32) };
+        self.flush_events(event_type as u32, event_type as u32);
     }
 
     /// Removes all events in the event queue that match the specified type range.
+    ///
+    /// The payloads of flushed custom events are freed.
     #[doc(alias = "SDL_FlushEvents")]
     pub fn flush_events(&self, min_type: u32, max_type: u32) {
-        unsafe { sys::SDL_FlushEvents(min_type, max_type) };
+        unsafe {
+            flush_custom_events(min_type, max_type);
+            sys::SDL_FlushEvents(min_type, max_type);
+        };
     }
 
     /// Reads the events at the front of the event queue, until the maximum amount
@@ -87,6 +233,7 @@ impl crate::EventSubsystem {
     where
         B: FromIterator<Event>,
     {
+        let max_amount = max_amount.min(MAX_QUEUED_EVENTS);
         unsafe {
             let mut events = Vec::with_capacity(max_amount as usize);
 
@@ -108,11 +255,133 @@ impl crate::EventSubsystem {
             } else {
                 events.set_len(result as usize);
 
-                events.into_iter().map(Event::from_ll).collect()
+                // The events are still owned by the queue.
+                events.iter().map(Event::from_ll_borrowed).collect()
+            }
+        }
+    }
+
+    /// Adds, reads or removes up to `max_amount` events of the event queue, returning the
+    /// affected events.
+    ///
+    /// * `PeepAction::Add(events)` adds the events to the back of the queue. The `range` is
+    ///   ignored.
+    /// * `PeepAction::Peek` reads the events at the front of the queue whose type is in
+    ///   `range`, without removing them.
+    /// * `PeepAction::Get` removes the events at the front of the queue whose type is in
+    ///   `range`.
+    ///
+    /// Events outside of `range` are left untouched, and unlike
+    /// [`EventSender::push_event`], added events do not go through the event filter and
+    /// watchers.
+    ///
+    /// # Example: remove the pending game controller events
+    /// ```no_run
+    /// use sdl2::event::{EventType, PeepAction};
+    ///
+    /// let sdl_context = sdl2::init().unwrap();
+    /// let event_subsystem = sdl_context.event().unwrap();
+    ///
+    /// let controller_events = event_subsystem
+    ///     .peep_events(
+    ///         PeepAction::Get,
+    ///         EventType::ControllerAxisMotion..=EventType::ControllerTouchpadUp,
+    ///         64,
+    ///     )
+    ///     .unwrap();
+    /// ```
+    #[doc(alias = "SDL_PeepEvents")]
+    pub fn peep_events(
+        &self,
+        action: PeepAction,
+        range: RangeInclusive<EventType>,
+        max_amount: u32,
+    ) -> Result<Vec<Event>, String> {
+        let (min_type, max_type) = (*range.start() as u32, *range.end() as u32);
+        let max_amount = max_amount.min(MAX_QUEUED_EVENTS);
+
+        match action {
+            PeepAction::Add(events) => {
+                let events = &events[..events.len().min(max_amount as usize)];
+                let mut raw_events = Vec::with_capacity(events.len());
+                for event in events {
+                    match event.to_ll() {
+                        Some(raw_event) => raw_events.push(raw_event),
+                        None => {
+                            for raw_event in &raw_events {
+                                unsafe { free_ll_event_strings(raw_event) };
+                            }
+                            return Err("Cannot add unsupported event type to the queue".to_owned());
+                        }
+                    }
+                }
+
+                let result = unsafe {
+                    sys::SDL_PeepEvents(
+                        raw_events.as_mut_ptr(),
+                        raw_events.len() as c_int,
+                        sys::SDL_eventaction::SDL_ADDEVENT,
+                        min_type,
+                        max_type,
+                    )
+                };
+                // The events that were added are now owned by the queue.
+                let added = result.max(0) as usize;
+                for raw_event in &raw_events[added..] {
+                    unsafe { free_ll_event_strings(raw_event) };
+                }
+                if result < 0 {
+                    Err(get_error())
+                } else {
+                    Ok(events[..added].to_vec())
+                }
+            }
+            PeepAction::Peek | PeepAction::Get => {
+                let mut events = Vec::with_capacity(max_amount as usize);
+                let peek = matches!(action, PeepAction::Peek);
+                let result = unsafe {
+                    sys::SDL_PeepEvents(
+                        events.as_mut_ptr(),
+                        max_amount as c_int,
+                        if peek {
+                            sys::SDL_eventaction::SDL_PEEKEVENT
+                        } else {
+                            sys::SDL_eventaction::SDL_GETEVENT
+                        },
+                        min_type,
+                        max_type,
+                    )
+                };
+                if result < 0 {
+                    return Err(get_error());
+                }
+                unsafe { events.set_len(result as usize) };
+
+                if peek {
+                    // The events are still owned by the queue.
+                    Ok(events.iter().map(Event::from_ll_borrowed).collect())
+                } else {
+                    Ok(events.into_iter().map(Event::from_ll).collect())
+                }
             }
         }
     }
 
+    /// Returns `true` if an event of type `event_type` is in the event queue.
+    #[doc(alias = "SDL_HasEvent")]
+    pub fn has_event(&self, event_type: EventType) -> bool {
+        unsafe { sys::SDL_HasEvent(event_type as u32) == sys::SDL_bool::SDL_TRUE }
+    }
+
+    /// Returns `true` if an event whose type is in `range` is in the event queue.
+    #[doc(alias = "SDL_HasEvents")]
+    pub fn has_events(&self, range: RangeInclusive<EventType>) -> bool {
+        unsafe {
+            sys::SDL_HasEvents(*range.start() as u32, *range.end() as u32)
+                == sys::SDL_bool::SDL_TRUE
+        }
+    }
+
     /// Pushes an event to the event queue.
     pub fn push_event(&self, event: Event) -> Result<(), String> {
         self.event_sender().push_event(event)
@@ -195,6 +464,11 @@ impl crate::EventSubsystem {
     /// [register_custom_event](#method.register_custom_event),
     /// this method will panic.
     ///
+    /// The payload is owned by the event until it is taken with [`Event::take_user_event`](event/enum.Event.html#method.take_user_event),
+    /// released with [`Event::discard_user_event`](event/enum.Event.html#method.discard_user_event), or flushed from the queue. A received
+    /// event that is dropped without one of these keeps its payload alive until the end of
+    /// the program.
+    ///
     /// # Example: pushing and receiving a custom event
     /// ```
     /// struct SomeCustomEvent {
@@ -212,15 +486,32 @@ impl crate::EventSubsystem {
     /// ev.push_custom_event(event);
     ///
     /// let received = ep.poll_event().unwrap(); // or within a for event in ep.poll_iter()
-    /// if received.is_user_event() {
-    ///     let e2 = received.as_user_event_type::<SomeCustomEvent>().unwrap();
-    ///     assert_eq!(e2.a, 42);
+    /// if let Ok(e2) = received.take_user_event::<SomeCustomEvent>() {
+    ///     assert_eq!(e2.payload().a, 42);
     /// }
     /// ```
-    pub fn push_custom_event<T: ::std::any::Any>(&self, event: T) -> Result<(), String> {
+    pub fn push_custom_event<T: ::std::any::Any + Send>(&self, event: T) -> Result<(), String> {
         self.event_sender().push_custom_event(event)
     }
 
+    /// Removes all custom events of type ``T`` from the event queue and frees their payloads.
+    ///
+    /// Does nothing if ``T`` was not registered using
+    /// [register_custom_event](#method.register_custom_event).
+    pub fn flush_custom_event<T: ::std::any::Any>(&self) {
+        use std::any::TypeId;
+        let sdl_id = CUSTOM_EVENT_TYPES
+            .lock()
+            .unwrap()
+            .type_id_to_sdl_id
+            .get(&TypeId::of::<Box<T>>())
+            .copied();
+
+        if let Some(sdl_id) = sdl_id {
+            self.flush_events(sdl_id, sdl_id);
+        }
+    }
+
     /// Create an event sender that can be sent to other threads.
     ///
     /// An `EventSender` will not keep the event subsystem alive. If the event subsystem is
@@ -252,6 +543,117 @@ impl crate::EventSubsystem {
     ) -> EventWatch<'a, CB> {
         EventWatch::add(callback)
     }
+
+    /// Create a stream of the events added to the event queue, for use from async code.
+    ///
+    /// Events are delivered to the stream as they are added to the queue: events from the
+    /// system are added while the thread running the `EventPump` pumps events (e.g. in
+    /// [`EventPump::wait_event_timeout`] or [`EventPump::poll_iter`]), and events pushed with
+    /// an [`EventSender`] are added on the pushing thread. The events are still delivered to
+    /// the `EventPump` as well, so the stream never consumes them.
+    ///
+    /// The stream can be sent to other threads and never ends. Events are buffered until the
+    /// stream is polled, and the stream stops receiving events when it is dropped. At most
+    /// 1024 events are buffered: if the stream is not polled fast enough, the oldest events
+    /// are dropped from it to make room for new ones.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sdl2::event::Event;
+    ///
+    /// let sdl = sdl2::init().unwrap();
+    /// let ev = sdl.event().unwrap();
+    /// let mut ep = sdl.event_pump().unwrap();
+    ///
+    /// let mut stream = ev.event_stream();
+    /// std::thread::spawn(move || loop {
+    ///     // From an async task, use `while let Some(event) = stream.next().await` instead.
+    ///     while let Some(event) = stream.try_next() {
+    ///         println!("{:?}", event);
+    ///     }
+    ///     std::thread::sleep(std::time::Duration::from_millis(10));
+    /// });
+    ///
+    /// loop {
+    ///     if let Some(Event::Quit { .. }) = ep.wait_event_timeout(100) {
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "futures")]
+    #[doc(alias = "SDL_AddEventWatch")]
+    pub fn event_stream(&self) -> EventStream {
+        EventStream::add()
+    }
+
+    /// Set up a filter which is called for every event before it is added to the event queue.
+    ///
+    /// If the filter returns `false`, the event is dropped from the queue, but SDL's internal
+    /// state (e.g. keyboard and mouse state) is still updated. Only one filter can be set at a
+    /// time: setting a new filter replaces the previous one.
+    ///
+    /// The filter is removed when the return value is dropped.
+    /// Just calling this function without binding to a variable immediately removes the filter.
+    ///
+    /// Note that the filter may be called from another thread, and that events pushed with
+    /// `SDL_PeepEvents` or disabled with `EventPump::disable_event` never reach it.
+    ///
+    /// # Example: drop every mouse motion event
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let sdl = sdl2::init().unwrap();
+    /// let ev = sdl.event().unwrap();
+    ///
+    /// // `let _ = ...` is insufficient, as it is dropped immediately.
+    /// let _event_filter = ev.set_event_filter(|event: &Event| {
+    ///     !matches!(event, Event::MouseMotion { .. })
+    /// });
+    /// ```
+    #[doc(alias = "SDL_SetEventFilter")]
+    pub fn set_event_filter<'a, CB: EventFilterCallback + 'a>(
+        &self,
+        callback: CB,
+    ) -> EventFilter<'a, CB> {
+        EventFilter::set(callback)
+    }
+
+    /// Runs `callback` on every event currently in the event queue, removing the events for
+    /// which it returns `false`.
+    ///
+    /// # Example: purge pending finger motion events
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let sdl = sdl2::init().unwrap();
+    /// let ev = sdl.event().unwrap();
+    ///
+    /// ev.filter_queued_events(|event: &Event| !matches!(event, Event::FingerMotion { .. }));
+    /// ```
+    #[doc(alias = "SDL_FilterEvents")]
+    pub fn filter_queued_events<CB: EventFilterCallback>(&self, mut callback: CB) {
+        unsafe {
+            sys::SDL_FilterEvents(
+                Some(event_filter_marshall::<CB> as _),
+                &mut callback as *mut _ as *mut c_void,
+            )
+        };
+    }
+}
+
+/// What [`EventSubsystem::peep_events`](../struct.EventSubsystem.html#method.peep_events)
+/// does with the event queue.
+#[derive(Copy, Clone, Debug)]
+pub enum PeepAction<'a> {
+    /// Add the events to the back of the queue.
+    #[doc(alias = "SDL_ADDEVENT")]
+    Add(&'a [Event]),
+    /// Read events from the front of the queue, without removing them.
+    #[doc(alias = "SDL_PEEKEVENT")]
+    Peek,
+    /// Remove events from the front of the queue.
+    #[doc(alias = "SDL_GETEVENT")]
+    Get,
 }
 
 /// Types of events that can be delivered.
@@ -267,6 +669,7 @@ pub enum EventType {
     AppDidEnterBackground = SDL_EventType::SDL_APP_DIDENTERBACKGROUND as u32,
     AppWillEnterForeground = SDL_EventType::SDL_APP_WILLENTERFOREGROUND as u32,
     AppDidEnterForeground = SDL_EventType::SDL_APP_DIDENTERFOREGROUND as u32,
+    LocaleChanged = SDL_EventType::SDL_LOCALECHANGED as u32,
 
     Display = SDL_EventType::SDL_DISPLAYEVENT as u32,
     Window = SDL_EventType::SDL_WINDOWEVENT as u32,
@@ -275,6 +678,8 @@ pub enum EventType {
     KeyUp = SDL_EventType::SDL_KEYUP as u32,
     TextEditing = SDL_EventType::SDL_TEXTEDITING as u32,
     TextInput = SDL_EventType::SDL_TEXTINPUT as u32,
+    KeymapChanged = SDL_EventType::SDL_KEYMAPCHANGED as u32,
+    TextEditingExt = SDL_EventType::SDL_TEXTEDITING_EXT as u32,
 
     MouseMotion = SDL_EventType::SDL_MOUSEMOTION as u32,
     MouseButtonDown = SDL_EventType::SDL_MOUSEBUTTONDOWN as u32,
@@ -320,6 +725,8 @@ pub enum EventType {
     RenderTargetsReset = SDL_EventType::SDL_RENDER_TARGETS_RESET as u32,
     RenderDeviceReset = SDL_EventType::SDL_RENDER_DEVICE_RESET as u32,
 
+    PollSentinel = SDL_EventType::SDL_POLLSENTINEL as u32,
+
     User = SDL_EventType::SDL_USEREVENT as u32,
     Last = SDL_EventType::SDL_LASTEVENT as u32,
 }
@@ -341,6 +748,7 @@ impl TryFrom<u32> for EventType {
             x if x == SDL_APP_DIDENTERBACKGROUND as u32 => Ok(AppDidEnterBackground),
             x if x == SDL_APP_WILLENTERFOREGROUND as u32 => Ok(AppWillEnterForeground),
             x if x == SDL_APP_DIDENTERFOREGROUND as u32 => Ok(AppDidEnterForeground),
+            x if x == SDL_LOCALECHANGED as u32 => Ok(LocaleChanged),
 
             x if x == SDL_DISPLAYEVENT as u32 => Ok(Display),
             x if x == SDL_WINDOWEVENT as u32 => Ok(Window),
@@ -349,6 +757,8 @@ impl TryFrom<u32> for EventType {
             x if x == SDL_KEYUP as u32 => Ok(KeyUp),
             x if x == SDL_TEXTEDITING as u32 => Ok(TextEditing),
             x if x == SDL_TEXTINPUT as u32 => Ok(TextInput),
+            x if x == SDL_KEYMAPCHANGED as u32 => Ok(KeymapChanged),
+            x if x == SDL_TEXTEDITING_EXT as u32 => Ok(TextEditingExt),
 
             x if x == SDL_MOUSEMOTION as u32 => Ok(MouseMotion),
             x if x == SDL_MOUSEBUTTONDOWN as u32 => Ok(MouseButtonDown),
@@ -394,6 +804,8 @@ impl TryFrom<u32> for EventType {
             x if x == SDL_RENDER_TARGETS_RESET as u32 => Ok(RenderTargetsReset),
             x if x == SDL_RENDER_DEVICE_RESET as u32 => Ok(RenderDeviceReset),
 
+            x if x == SDL_POLLSENTINEL as u32 => Ok(PollSentinel),
+
             x if x == SDL_USEREVENT as u32 => Ok(User),
             x if x == SDL_LASTEVENT as u32 => Ok(Last),
 
@@ -560,6 +972,10 @@ pub enum Event {
     AppDidEnterForeground {
         timestamp: u32,
     },
+    /// The user's locale preferences have changed (added in 2.0.14)
+    LocaleChanged {
+        timestamp: u32,
+    },
 
     Display {
         timestamp: u32,
@@ -603,6 +1019,23 @@ pub enum Event {
         text: String,
     },
 
+    /// The keymap changed due to a system event such as an input language or keyboard layout
+    /// change (added in 2.0.14)
+    KeymapChanged {
+        timestamp: u32,
+    },
+
+    /// Like `TextEditing`, for composition strings too long to fit in it (added in 2.0.22)
+    ///
+    /// Only sent if `sdl2::hint::set_ime_support_extended_text` is enabled.
+    TextEditingExt {
+        timestamp: u32,
+        window_id: u32,
+        text: String,
+        start: i32,
+        length: i32,
+    },
+
     MouseMotion {
         timestamp: u32,
         window_id: u32,
@@ -897,6 +1330,12 @@ pub enum Event {
         timestamp: u32,
     },
 
+    /// Marks the events that were in the queue when `SDL_PollEvent` pumped the event loop
+    /// (added in 2.0.22)
+    PollSentinel {
+        timestamp: u32,
+    },
+
     User {
         timestamp: u32,
         window_id: u32,
@@ -922,6 +1361,36 @@ unsafe impl Send for Event {}
 /// requires using `unsafe` and ensuring your own safety guarantees.
 unsafe impl Sync for Event {}
 
+/// A custom event whose payload has been taken out of an `Event::User` with
+/// [`Event::take_user_event`].
+///
+/// Unlike `Event::User`, this owns its payload: it is dropped along with the `CustomEvent`,
+/// and the event can only be cloned if the payload can.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CustomEvent<T> {
+    pub timestamp: u32,
+    pub window_id: u32,
+    pub code: i32,
+    payload: T,
+}
+
+impl<T> CustomEvent<T> {
+    /// Returns a reference to the payload pushed with `push_custom_event`.
+    pub fn payload(&self) -> &T {
+        &self.payload
+    }
+
+    /// Returns a mutable reference to the payload pushed with `push_custom_event`.
+    pub fn payload_mut(&mut self) -> &mut T {
+        &mut self.payload
+    }
+
+    /// Consumes the event, returning its payload.
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+}
+
 /// Helper function to make converting scancodes
 /// and keycodes to primitive `SDL_Keysym` types.
 #[doc(alias = "SDL_Keysym")]
@@ -947,12 +1416,27 @@ where
     }
 }
 
+/// Helper function to copy text into the fixed-size, nul-terminated
+/// buffer of `SDL_TextEditingEvent` and `SDL_TextInputEvent`.
+///
+/// Returns `None` if the text does not fit.
+fn mk_text_buffer(text: &str) -> Option<[c_char; sys::SDL_TEXTINPUTEVENT_TEXT_SIZE as usize]> {
+    let mut buf = [0; sys::SDL_TEXTINPUTEVENT_TEXT_SIZE as usize];
+    if text.len() >= buf.len() || text.bytes().any(|b| b == 0) {
+        return None;
+    }
+    for (dst, &src) in buf.iter_mut().zip(text.as_bytes()) {
+        *dst = src as c_char;
+    }
+    Some(buf)
+}
+
 // TODO: Remove this when from_utf8 is updated in Rust
 // This would honestly be nice if it took &self instead of self,
 // but Event::User's raw pointers kind of removes that possibility.
 impl Event {
     fn to_ll(&self) -> Option<sys::SDL_Event> {
-        let mut ret = mem::MaybeUninit::uninit();
+        let mut ret = mem::MaybeUninit::zeroed();
         match *self {
             Event::User {
                 window_id,
@@ -962,6 +1446,12 @@ impl Event {
                 data2,
                 timestamp,
             } => {
+                // Any other type would be read back as another kind of event.
+                if type_ < SDL_EventType::SDL_USEREVENT as u32
+                    || type_ >= SDL_EventType::SDL_LASTEVENT as u32
+                {
+                    return None;
+                }
                 let event = sys::SDL_UserEvent {
                     type_,
                     timestamp,
@@ -1458,76 +1948,723 @@ impl Event {
                 }
             }
 
-            Event::FingerDown { .. }
-            | Event::FingerUp { .. }
-            | Event::FingerMotion { .. }
-            | Event::DollarGesture { .. }
-            | Event::DollarRecord { .. }
-            | Event::MultiGesture { .. }
-            | Event::ClipboardUpdate { .. }
-            | Event::DropFile { .. }
-            | Event::TextEditing { .. }
-            | Event::TextInput { .. }
-            | Event::Unknown { .. }
-            | _ => {
-                // don't know how to convert!
-                None
+            Event::AppTerminating { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_APP_TERMINATING as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
+                }
             }
-        }
-    }
-
-    pub fn from_ll(raw: sys::SDL_Event) -> Event {
-        let raw_type = unsafe { raw.type_ };
 
-        // if event type has not been defined, treat it as a UserEvent
-        let event_type: EventType = EventType::try_from(raw_type).unwrap_or(EventType::User);
-        unsafe {
-            match event_type {
-                EventType::Quit => {
-                    let event = raw.quit;
-                    Event::Quit {
-                        timestamp: event.timestamp,
-                    }
-                }
-                EventType::AppTerminating => {
-                    let event = raw.common;
-                    Event::AppTerminating {
-                        timestamp: event.timestamp,
-                    }
-                }
-                EventType::AppLowMemory => {
-                    let event = raw.common;
-                    Event::AppLowMemory {
-                        timestamp: event.timestamp,
-                    }
+            Event::AppLowMemory { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_APP_LOWMEMORY as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
                 }
-                EventType::AppWillEnterBackground => {
-                    let event = raw.common;
-                    Event::AppWillEnterBackground {
-                        timestamp: event.timestamp,
-                    }
+            }
+
+            Event::AppWillEnterBackground { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_APP_WILLENTERBACKGROUND as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
                 }
-                EventType::AppDidEnterBackground => {
-                    let event = raw.common;
-                    Event::AppDidEnterBackground {
-                        timestamp: event.timestamp,
-                    }
+            }
+
+            Event::AppDidEnterBackground { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_APP_DIDENTERBACKGROUND as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
                 }
-                EventType::AppWillEnterForeground => {
-                    let event = raw.common;
-                    Event::AppWillEnterForeground {
-                        timestamp: event.timestamp,
-                    }
+            }
+
+            Event::AppWillEnterForeground { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_APP_WILLENTERFOREGROUND as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
                 }
-                EventType::AppDidEnterForeground => {
-                    let event = raw.common;
-                    Event::AppDidEnterForeground {
-                        timestamp: event.timestamp,
-                    }
+            }
+
+            Event::AppDidEnterForeground { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_APP_DIDENTERFOREGROUND as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
                 }
+            }
 
-                EventType::Display => {
-                    let event = raw.display;
+            Event::LocaleChanged { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_LOCALECHANGED as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::TextEditing {
+                timestamp,
+                window_id,
+                ref text,
+                start,
+                length,
+            } => {
+                let event = sys::SDL_TextEditingEvent {
+                    type_: SDL_EventType::SDL_TEXTEDITING as u32,
+                    timestamp,
+                    windowID: window_id,
+                    text: mk_text_buffer(text)?,
+                    start,
+                    length,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_TextEditingEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::TextInput {
+                timestamp,
+                window_id,
+                ref text,
+            } => {
+                let event = sys::SDL_TextInputEvent {
+                    type_: SDL_EventType::SDL_TEXTINPUT as u32,
+                    timestamp,
+                    windowID: window_id,
+                    text: mk_text_buffer(text)?,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_TextInputEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::KeymapChanged { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_KEYMAPCHANGED as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::TextEditingExt {
+                timestamp,
+                window_id,
+                ref text,
+                start,
+                length,
+            } => {
+                // The string is freed by `Event::from_ll` once the event is polled.
+                let text = CString::new(text.as_str()).ok()?;
+                let event = sys::SDL_TextEditingExtEvent {
+                    type_: SDL_EventType::SDL_TEXTEDITING_EXT as u32,
+                    timestamp,
+                    windowID: window_id,
+                    text: unsafe { sys::SDL_strdup(text.as_ptr()) },
+                    start,
+                    length,
+                };
+                if event.text.is_null() {
+                    return None;
+                }
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_TextEditingExtEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::ControllerTouchpadDown {
+                timestamp,
+                which,
+                touchpad,
+                finger,
+                x,
+                y,
+                pressure,
+            } => {
+                let event = sys::SDL_ControllerTouchpadEvent {
+                    type_: SDL_EventType::SDL_CONTROLLERTOUCHPADDOWN as u32,
+                    timestamp,
+                    which: which as i32,
+                    touchpad: touchpad as i32,
+                    finger: finger as i32,
+                    x,
+                    y,
+                    pressure,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_ControllerTouchpadEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::ControllerTouchpadMotion {
+                timestamp,
+                which,
+                touchpad,
+                finger,
+                x,
+                y,
+                pressure,
+            } => {
+                let event = sys::SDL_ControllerTouchpadEvent {
+                    type_: SDL_EventType::SDL_CONTROLLERTOUCHPADMOTION as u32,
+                    timestamp,
+                    which: which as i32,
+                    touchpad: touchpad as i32,
+                    finger: finger as i32,
+                    x,
+                    y,
+                    pressure,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_ControllerTouchpadEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::ControllerTouchpadUp {
+                timestamp,
+                which,
+                touchpad,
+                finger,
+                x,
+                y,
+                pressure,
+            } => {
+                let event = sys::SDL_ControllerTouchpadEvent {
+                    type_: SDL_EventType::SDL_CONTROLLERTOUCHPADUP as u32,
+                    timestamp,
+                    which: which as i32,
+                    touchpad: touchpad as i32,
+                    finger: finger as i32,
+                    x,
+                    y,
+                    pressure,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_ControllerTouchpadEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            #[cfg(feature = "hidapi")]
+            Event::ControllerSensorUpdated {
+                timestamp,
+                which,
+                sensor,
+                data,
+            } => {
+                let event = sys::SDL_ControllerSensorEvent {
+                    type_: SDL_EventType::SDL_CONTROLLERSENSORUPDATE as u32,
+                    timestamp,
+                    which: which as i32,
+                    sensor: sys::SDL_SensorType::from(sensor) as i32,
+                    data,
+                    timestamp_us: 0,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_ControllerSensorEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::FingerDown {
+                timestamp,
+                touch_id,
+                finger_id,
+                x,
+                y,
+                dx,
+                dy,
+                pressure,
+            } => {
+                let event = sys::SDL_TouchFingerEvent {
+                    type_: SDL_EventType::SDL_FINGERDOWN as u32,
+                    timestamp,
+                    touchId: touch_id,
+                    fingerId: finger_id,
+                    x,
+                    y,
+                    dx,
+                    dy,
+                    pressure,
+                    windowID: 0,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_TouchFingerEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::FingerUp {
+                timestamp,
+                touch_id,
+                finger_id,
+                x,
+                y,
+                dx,
+                dy,
+                pressure,
+            } => {
+                let event = sys::SDL_TouchFingerEvent {
+                    type_: SDL_EventType::SDL_FINGERUP as u32,
+                    timestamp,
+                    touchId: touch_id,
+                    fingerId: finger_id,
+                    x,
+                    y,
+                    dx,
+                    dy,
+                    pressure,
+                    windowID: 0,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_TouchFingerEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::FingerMotion {
+                timestamp,
+                touch_id,
+                finger_id,
+                x,
+                y,
+                dx,
+                dy,
+                pressure,
+            } => {
+                let event = sys::SDL_TouchFingerEvent {
+                    type_: SDL_EventType::SDL_FINGERMOTION as u32,
+                    timestamp,
+                    touchId: touch_id,
+                    fingerId: finger_id,
+                    x,
+                    y,
+                    dx,
+                    dy,
+                    pressure,
+                    windowID: 0,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_TouchFingerEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::DollarGesture {
+                timestamp,
+                touch_id,
+                gesture_id,
+                num_fingers,
+                error,
+                x,
+                y,
+            } => {
+                let event = sys::SDL_DollarGestureEvent {
+                    type_: SDL_EventType::SDL_DOLLARGESTURE as u32,
+                    timestamp,
+                    touchId: touch_id,
+                    gestureId: gesture_id,
+                    numFingers: num_fingers,
+                    error,
+                    x,
+                    y,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_DollarGestureEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::DollarRecord {
+                timestamp,
+                touch_id,
+                gesture_id,
+                num_fingers,
+                error,
+                x,
+                y,
+            } => {
+                let event = sys::SDL_DollarGestureEvent {
+                    type_: SDL_EventType::SDL_DOLLARRECORD as u32,
+                    timestamp,
+                    touchId: touch_id,
+                    gestureId: gesture_id,
+                    numFingers: num_fingers,
+                    error,
+                    x,
+                    y,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_DollarGestureEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::MultiGesture {
+                timestamp,
+                touch_id,
+                d_theta,
+                d_dist,
+                x,
+                y,
+                num_fingers,
+            } => {
+                let event = sys::SDL_MultiGestureEvent {
+                    type_: SDL_EventType::SDL_MULTIGESTURE as u32,
+                    timestamp,
+                    touchId: touch_id,
+                    dTheta: d_theta,
+                    dDist: d_dist,
+                    x,
+                    y,
+                    numFingers: num_fingers,
+                    padding: 0,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_MultiGestureEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::ClipboardUpdate { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_CLIPBOARDUPDATE as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::DropFile {
+                timestamp,
+                window_id,
+                ref filename,
+            } => {
+                // The string is freed by `Event::from_ll` once the event is polled.
+                let filename = CString::new(filename.as_str()).ok()?;
+                let event = sys::SDL_DropEvent {
+                    type_: SDL_EventType::SDL_DROPFILE as u32,
+                    timestamp,
+                    file: unsafe { sys::SDL_strdup(filename.as_ptr()) },
+                    windowID: window_id,
+                };
+                if event.file.is_null() {
+                    return None;
+                }
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_DropEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::DropText {
+                timestamp,
+                window_id,
+                ref filename,
+            } => {
+                // The string is freed by `Event::from_ll` once the event is polled.
+                let filename = CString::new(filename.as_str()).ok()?;
+                let event = sys::SDL_DropEvent {
+                    type_: SDL_EventType::SDL_DROPTEXT as u32,
+                    timestamp,
+                    file: unsafe { sys::SDL_strdup(filename.as_ptr()) },
+                    windowID: window_id,
+                };
+                if event.file.is_null() {
+                    return None;
+                }
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_DropEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::DropBegin {
+                timestamp,
+                window_id,
+            } => {
+                let event = sys::SDL_DropEvent {
+                    type_: SDL_EventType::SDL_DROPBEGIN as u32,
+                    timestamp,
+                    file: ptr::null_mut(),
+                    windowID: window_id,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_DropEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::DropComplete {
+                timestamp,
+                window_id,
+            } => {
+                let event = sys::SDL_DropEvent {
+                    type_: SDL_EventType::SDL_DROPCOMPLETE as u32,
+                    timestamp,
+                    file: ptr::null_mut(),
+                    windowID: window_id,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_DropEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::AudioDeviceAdded {
+                timestamp,
+                which,
+                iscapture,
+            } => {
+                let event = sys::SDL_AudioDeviceEvent {
+                    type_: SDL_EventType::SDL_AUDIODEVICEADDED as u32,
+                    timestamp,
+                    which,
+                    iscapture: iscapture as u8,
+                    padding1: 0,
+                    padding2: 0,
+                    padding3: 0,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_AudioDeviceEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::AudioDeviceRemoved {
+                timestamp,
+                which,
+                iscapture,
+            } => {
+                let event = sys::SDL_AudioDeviceEvent {
+                    type_: SDL_EventType::SDL_AUDIODEVICEREMOVED as u32,
+                    timestamp,
+                    which,
+                    iscapture: iscapture as u8,
+                    padding1: 0,
+                    padding2: 0,
+                    padding3: 0,
+                };
+                unsafe {
+                    ptr::copy(
+                        &event,
+                        ret.as_mut_ptr() as *mut sys::SDL_AudioDeviceEvent,
+                        1,
+                    );
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::RenderTargetsReset { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_RENDER_TARGETS_RESET as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::RenderDeviceReset { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_RENDER_DEVICE_RESET as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::PollSentinel { timestamp } => {
+                let event = sys::SDL_CommonEvent {
+                    type_: SDL_EventType::SDL_POLLSENTINEL as u32,
+                    timestamp,
+                };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+
+            Event::Unknown { timestamp, type_ } => {
+                // Known and user event types would be read back as another kind of event.
+                if EventType::try_from(type_).is_ok()
+                    || type_ >= SDL_EventType::SDL_USEREVENT as u32
+                {
+                    return None;
+                }
+                let event = sys::SDL_CommonEvent { type_, timestamp };
+                unsafe {
+                    ptr::copy(&event, ret.as_mut_ptr() as *mut sys::SDL_CommonEvent, 1);
+                    Some(ret.assume_init())
+                }
+            }
+        }
+    }
+
+    pub fn from_ll(raw: sys::SDL_Event) -> Event {
+        let event = Event::from_ll_borrowed(&raw);
+        unsafe {
+            free_ll_event_strings(&raw);
+            if raw.type_ >= SDL_EventType::SDL_USEREVENT as u32 {
+                let on_received = CUSTOM_EVENT_TYPES
+                    .lock()
+                    .unwrap()
+                    .take_on_received(raw.user.type_, raw.user.data2 as usize);
+                if let Some(on_received) = on_received {
+                    on_received(true);
+                }
+            }
+        }
+        event
+    }
+
+    /// Converts a raw event without taking ownership of the strings it points to, for events
+    /// that are still owned by SDL (e.g. in event watch and filter callbacks).
+    fn from_ll_borrowed(raw: &sys::SDL_Event) -> Event {
+        let raw_type = unsafe { raw.type_ };
+
+        // if event type has not been defined, treat it as a UserEvent
+        let event_type: EventType = EventType::try_from(raw_type).unwrap_or(EventType::User);
+        unsafe {
+            match event_type {
+                EventType::Quit => {
+                    let event = raw.quit;
+                    Event::Quit {
+                        timestamp: event.timestamp,
+                    }
+                }
+                EventType::AppTerminating => {
+                    let event = raw.common;
+                    Event::AppTerminating {
+                        timestamp: event.timestamp,
+                    }
+                }
+                EventType::AppLowMemory => {
+                    let event = raw.common;
+                    Event::AppLowMemory {
+                        timestamp: event.timestamp,
+                    }
+                }
+                EventType::AppWillEnterBackground => {
+                    let event = raw.common;
+                    Event::AppWillEnterBackground {
+                        timestamp: event.timestamp,
+                    }
+                }
+                EventType::AppDidEnterBackground => {
+                    let event = raw.common;
+                    Event::AppDidEnterBackground {
+                        timestamp: event.timestamp,
+                    }
+                }
+                EventType::AppWillEnterForeground => {
+                    let event = raw.common;
+                    Event::AppWillEnterForeground {
+                        timestamp: event.timestamp,
+                    }
+                }
+                EventType::AppDidEnterForeground => {
+                    let event = raw.common;
+                    Event::AppDidEnterForeground {
+                        timestamp: event.timestamp,
+                    }
+                }
+                EventType::LocaleChanged => {
+                    let event = raw.common;
+                    Event::LocaleChanged {
+                        timestamp: event.timestamp,
+                    }
+                }
+
+                EventType::Display => {
+                    let event = raw.display;
 
                     Event::Display {
                         timestamp: event.timestamp,
@@ -1607,6 +2744,29 @@ impl Event {
                         text,
                     }
                 }
+                EventType::KeymapChanged => {
+                    let event = raw.common;
+                    Event::KeymapChanged {
+                        timestamp: event.timestamp,
+                    }
+                }
+                EventType::TextEditingExt => {
+                    let event = raw.editExt;
+
+                    let text = if event.text.is_null() {
+                        String::new()
+                    } else {
+                        let buf = CStr::from_ptr(event.text as *const _).to_bytes();
+                        String::from_utf8_lossy(buf).to_string()
+                    };
+                    Event::TextEditingExt {
+                        timestamp: event.timestamp,
+                        window_id: event.windowID,
+                        text,
+                        start: event.start,
+                        length: event.length,
+                    }
+                }
 
                 EventType::MouseMotion => {
                     let event = raw.motion;
@@ -1912,7 +3072,6 @@ impl Event {
 
                     let buf = CStr::from_ptr(event.file as *const _).to_bytes();
                     let text = String::from_utf8_lossy(buf).to_string();
-                    sys::SDL_free(event.file as *mut c_void);
 
                     Event::DropFile {
                         timestamp: event.timestamp,
@@ -1925,7 +3084,6 @@ impl Event {
 
                     let buf = CStr::from_ptr(event.file as *const _).to_bytes();
                     let text = String::from_utf8_lossy(buf).to_string();
-                    sys::SDL_free(event.file as *mut c_void);
 
                     Event::DropText {
                         timestamp: event.timestamp,
@@ -1975,6 +3133,10 @@ impl Event {
                     timestamp: raw.common.timestamp,
                 },
 
+                EventType::PollSentinel => Event::PollSentinel {
+                    timestamp: raw.common.timestamp,
+                },
+
                 EventType::First => panic!("Unused event, EventType::First, was encountered"),
                 EventType::Last => panic!("Unusable event, EventType::Last, was encountered"),
 
@@ -2011,31 +3173,132 @@ impl Event {
         matches!(self, Event::User { .. })
     }
 
-    pub fn as_user_event_type<T: ::std::any::Any>(&self) -> Option<T> {
-        use std::any::TypeId;
-        let type_id = TypeId::of::<Box<T>>();
-
-        let (event_id, event_box_ptr) = match *self {
-            Event::User { type_, data1, .. } => (type_, data1),
-            _ => return None,
+    /// Takes the payload out of a custom event pushed with
+    /// [EventSender::push_custom_event](struct.EventSender.html#method.push_custom_event).
+    ///
+    /// Returns `None` if this is not a custom event of type ``T``, or if its payload has
+    /// already been taken (e.g. from a clone of this event).
+    #[deprecated(note = "use `Event::take_user_event` instead")]
+    pub fn as_user_event_type<T: ::std::any::Any>(&self) -> Option<T> {
+        match *self {
+            Event::User { type_, data2, .. } => {
+                let payload = CUSTOM_EVENT_TYPES
+                    .lock()
+                    .unwrap()
+                    .take_payload::<T>(type_, data2 as usize);
+                payload.map(|payload| unsafe { payload.into_inner() })
+            }
+            _ => None,
+        }
+    }
+
+    /// Consumes a custom event pushed with
+    /// [EventSender::push_custom_event](struct.EventSender.html#method.push_custom_event)
+    /// and takes ownership of its payload.
+    ///
+    /// The payload of a custom event can only be taken once: copies of the same event
+    /// (e.g. obtained with `clone` or `EventSubsystem::peek_events`) return `Err` once it has
+    /// been taken. Payloads of custom events that are never taken are freed when they are
+    /// flushed from the queue with `EventSubsystem::flush_event` or
+    /// `EventSubsystem::flush_custom_event`. A custom event removed from the queue (e.g. with
+    /// `EventPump::poll_event`) and dropped without being taken leaks its payload; release it
+    /// with [`discard_user_event`](#method.discard_user_event) instead.
+    ///
+    /// Returns the event back in `Err` if it is not a custom event of type ``T``, so that it
+    /// can be handled further.
+    ///
+    /// # Example
+    /// ```
+    /// struct SomeCustomEvent {
+    ///     a: i32
+    /// }
+    ///
+    /// let sdl = sdl2::init().unwrap();
+    /// let ev = sdl.event().unwrap();
+    /// let mut ep = sdl.event_pump().unwrap();
+    ///
+    /// ev.register_custom_event::<SomeCustomEvent>().unwrap();
+    /// ev.push_custom_event(SomeCustomEvent { a: 42 }).unwrap();
+    ///
+    /// let received = ep.poll_event().unwrap();
+    /// let copy = received.clone();
+    ///
+    /// let custom = received.take_user_event::<SomeCustomEvent>().ok().unwrap();
+    /// assert_eq!(custom.payload().a, 42);
+    ///
+    /// // The payload has already been taken.
+    /// assert!(copy.take_user_event::<SomeCustomEvent>().is_err());
+    /// ```
+    pub fn take_user_event<T: ::std::any::Any>(self) -> Result<CustomEvent<T>, Event> {
+        let payload = match self {
+            Event::User { type_, data2, .. } => CUSTOM_EVENT_TYPES
+                .lock()
+                .unwrap()
+                .take_payload::<T>(type_, data2 as usize),
+            _ => None,
+        };
+        let payload = payload.map(|payload| unsafe { payload.into_inner::<T>() });
+
+        match (self, payload) {
+            (
+                Event::User {
+                    timestamp,
+                    window_id,
+                    code,
+                    ..
+                },
+                Some(payload),
+            ) => Ok(CustomEvent {
+                timestamp,
+                window_id,
+                code,
+                payload,
+            }),
+            (event, _) => Err(event),
+        }
+    }
+
+    /// Frees the payload of a custom event that will not be taken, whatever its type.
+    ///
+    /// Returns `false` if this is not a custom event, or if its payload has already been
+    /// taken or freed. A custom event that is still in the queue, e.g. one obtained with
+    /// `EventSubsystem::peek_events`, can no longer be taken once discarded.
+    ///
+    /// # Example
+    /// ```no_run
+    /// struct SomeCustomEvent;
+    ///
+    /// let sdl = sdl2::init().unwrap();
+    /// let ev = sdl.event().unwrap();
+    /// let mut ep = sdl.event_pump().unwrap();
+    /// ev.register_custom_event::<SomeCustomEvent>().unwrap();
+    ///
+    /// for event in ep.poll_iter() {
+    ///     match event {
+    ///         sdl2::event::Event::Quit { .. } => break,
+    ///         // Custom events the application does not handle
+    ///         event if event.is_user_event() => {
+    ///             event.discard_user_event();
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn discard_user_event(self) -> bool {
+        let payload = match self {
+            Event::User { type_, data2, .. } => CUSTOM_EVENT_TYPES
+                .lock()
+                .unwrap()
+                .remove_payload(type_, data2 as usize),
+            _ => None,
         };
-
-        let cet = CUSTOM_EVENT_TYPES.lock().unwrap();
-
-        let event_type_id = match cet.sdl_id_to_type_id.get(&event_id) {
-            Some(id) => id,
-            None => {
-                panic!("internal error; could not find typeid")
+        match payload {
+            Some(payload) => {
+                payload.free();
+                true
             }
-        };
-
-        if &type_id != event_type_id {
-            return None;
+            None => false,
         }
-
-        let event_box: Box<T> = unsafe { Box::from_raw(event_box_ptr as *mut T) };
-
-        Some(*event_box)
     }
 
     /// Returns `true` if they are the same "kind" of events.
@@ -2068,12 +3331,15 @@ impl Event {
             | (Self::AppDidEnterBackground { .. }, Self::AppDidEnterBackground { .. })
             | (Self::AppWillEnterForeground { .. }, Self::AppWillEnterForeground { .. })
             | (Self::AppDidEnterForeground { .. }, Self::AppDidEnterForeground { .. })
+            | (Self::LocaleChanged { .. }, Self::LocaleChanged { .. })
             | (Self::Display { .. }, Self::Display { .. })
             | (Self::Window { .. }, Self::Window { .. })
             | (Self::KeyDown { .. }, Self::KeyDown { .. })
             | (Self::KeyUp { .. }, Self::KeyUp { .. })
             | (Self::TextEditing { .. }, Self::TextEditing { .. })
             | (Self::TextInput { .. }, Self::TextInput { .. })
+            | (Self::KeymapChanged { .. }, Self::KeymapChanged { .. })
+            | (Self::TextEditingExt { .. }, Self::TextEditingExt { .. })
             | (Self::MouseMotion { .. }, Self::MouseMotion { .. })
             | (Self::MouseButtonDown { .. }, Self::MouseButtonDown { .. })
             | (Self::MouseButtonUp { .. }, Self::MouseButtonUp { .. })
@@ -2106,6 +3372,7 @@ impl Event {
             | (Self::AudioDeviceRemoved { .. }, Self::AudioDeviceRemoved { .. })
             | (Self::RenderTargetsReset { .. }, Self::RenderTargetsReset { .. })
             | (Self::RenderDeviceReset { .. }, Self::RenderDeviceReset { .. })
+            | (Self::PollSentinel { .. }, Self::PollSentinel { .. })
             | (Self::User { .. }, Self::User { .. })
             | (Self::Unknown { .. }, Self::Unknown { .. }) => true,
             #[cfg(feature = "hidapi")]
@@ -2137,12 +3404,15 @@ impl Event {
             Self::AppDidEnterBackground { timestamp, .. } => timestamp,
             Self::AppWillEnterForeground { timestamp, .. } => timestamp,
             Self::AppDidEnterForeground { timestamp, .. } => timestamp,
+            Self::LocaleChanged { timestamp, .. } => timestamp,
             Self::Display { timestamp, .. } => timestamp,
             Self::Window { timestamp, .. } => timestamp,
             Self::KeyDown { timestamp, .. } => timestamp,
             Self::KeyUp { timestamp, .. } => timestamp,
             Self::TextEditing { timestamp, .. } => timestamp,
             Self::TextInput { timestamp, .. } => timestamp,
+            Self::KeymapChanged { timestamp, .. } => timestamp,
+            Self::TextEditingExt { timestamp, .. } => timestamp,
             Self::MouseMotion { timestamp, .. } => timestamp,
             Self::MouseButtonDown { timestamp, .. } => timestamp,
             Self::MouseButtonUp { timestamp, .. } => timestamp,
@@ -2180,6 +3450,7 @@ impl Event {
             Self::AudioDeviceRemoved { timestamp, .. } => timestamp,
             Self::RenderTargetsReset { timestamp, .. } => timestamp,
             Self::RenderDeviceReset { timestamp, .. } => timestamp,
+            Self::PollSentinel { timestamp, .. } => timestamp,
             Self::User { timestamp, .. } => timestamp,
             Self::Unknown { timestamp, .. } => timestamp,
         }
@@ -2212,6 +3483,7 @@ impl Event {
             Self::KeyUp { window_id, .. } => Some(*window_id),
             Self::TextEditing { window_id, .. } => Some(*window_id),
             Self::TextInput { window_id, .. } => Some(*window_id),
+            Self::TextEditingExt { window_id, .. } => Some(*window_id),
             Self::MouseMotion { window_id, .. } => Some(*window_id),
             Self::MouseButtonDown { window_id, .. } => Some(*window_id),
             Self::MouseButtonUp { window_id, .. } => Some(*window_id),
@@ -2310,7 +3582,10 @@ impl Event {
     /// assert!(another_ev.is_text() == false); // Not a text event!
     /// ```
     pub fn is_text(&self) -> bool {
-        matches!(self, Self::TextEditing { .. } | Self::TextInput { .. })
+        matches!(
+            self,
+            Self::TextEditing { .. } | Self::TextInput { .. } | Self::TextEditingExt { .. }
+        )
     }
 
     /// Returns `true` if this is a mouse event.
@@ -2386,591 +3661,1501 @@ impl Event {
     ///     timestamp: 0,
     ///     which: 0,
     /// };
-    /// assert!(ev.is_controller());
+    /// assert!(ev.is_controller());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_controller() == false); // Not a controller event!
+    /// ```
+    pub fn is_controller(&self) -> bool {
+        matches!(
+            self,
+            Self::ControllerAxisMotion { .. }
+                | Self::ControllerButtonDown { .. }
+                | Self::ControllerButtonUp { .. }
+                | Self::ControllerDeviceAdded { .. }
+                | Self::ControllerDeviceRemoved { .. }
+                | Self::ControllerDeviceRemapped { .. }
+        )
+    }
+
+    /// Returns `true` if this is a joy event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::JoyButtonUp {
+    ///     timestamp: 0,
+    ///     which: 0,
+    ///     button_idx: 0,
+    /// };
+    /// assert!(ev.is_joy());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_joy() == false); // Not a joy event!
+    /// ```
+    pub fn is_joy(&self) -> bool {
+        matches!(
+            self,
+            Self::JoyAxisMotion { .. }
+                | Self::JoyBallMotion { .. }
+                | Self::JoyHatMotion { .. }
+                | Self::JoyButtonDown { .. }
+                | Self::JoyButtonUp { .. }
+                | Self::JoyDeviceAdded { .. }
+                | Self::JoyDeviceRemoved { .. }
+        )
+    }
+
+    /// Returns `true` if this is a finger event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::FingerMotion {
+    ///     timestamp: 0,
+    ///     touch_id: 0,
+    ///     finger_id: 0,
+    ///     x: 0.,
+    ///     y: 0.,
+    ///     dx: 0.,
+    ///     dy: 0.,
+    ///     pressure: 0.,
+    /// };
+    /// assert!(ev.is_finger());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_finger() == false); // Not a finger event!
+    /// ```
+    pub fn is_finger(&self) -> bool {
+        matches!(
+            self,
+            Self::FingerDown { .. } | Self::FingerUp { .. } | Self::FingerMotion { .. }
+        )
+    }
+
+    /// Returns `true` if this is a dollar event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::DollarGesture {
+    ///     timestamp: 0,
+    ///     touch_id: 0,
+    ///     gesture_id: 0,
+    ///     num_fingers: 0,
+    ///     error: 0.,
+    ///     x: 0.,
+    ///     y: 0.,
+    /// };
+    /// assert!(ev.is_dollar());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_dollar() == false); // Not a dollar event!
+    /// ```
+    pub fn is_dollar(&self) -> bool {
+        matches!(self, Self::DollarGesture { .. } | Self::DollarRecord { .. })
+    }
+
+    /// Returns `true` if this is a drop event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::DropBegin {
+    ///     timestamp: 0,
+    ///     window_id: 3,
+    /// };
+    /// assert!(ev.is_drop());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_drop() == false); // Not a drop event!
+    /// ```
+    pub fn is_drop(&self) -> bool {
+        matches!(
+            self,
+            Self::DropFile { .. }
+                | Self::DropText { .. }
+                | Self::DropBegin { .. }
+                | Self::DropComplete { .. }
+        )
+    }
+
+    /// Returns `true` if this is an audio event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::AudioDeviceAdded {
+    ///     timestamp: 0,
+    ///     which: 3,
+    ///     iscapture: false,
+    /// };
+    /// assert!(ev.is_audio());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_audio() == false); // Not an audio event!
+    /// ```
+    pub fn is_audio(&self) -> bool {
+        matches!(
+            self,
+            Self::AudioDeviceAdded { .. } | Self::AudioDeviceRemoved { .. }
+        )
+    }
+
+    /// Returns `true` if this is a render event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::RenderTargetsReset {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(ev.is_render());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_render() == false); // Not a render event!
+    /// ```
+    pub fn is_render(&self) -> bool {
+        matches!(
+            self,
+            Self::RenderTargetsReset { .. } | Self::RenderDeviceReset { .. }
+        )
+    }
+
+    /// Returns `true` if this is a locale changed event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::LocaleChanged {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(ev.is_locale_changed());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_locale_changed() == false); // Not a locale changed event!
+    /// ```
+    pub fn is_locale_changed(&self) -> bool {
+        matches!(self, Self::LocaleChanged { .. })
+    }
+
+    /// Returns `true` if this is a keymap changed event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::KeymapChanged {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(ev.is_keymap_changed());
+    ///
+    /// let another_ev = Event::Quit {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(another_ev.is_keymap_changed() == false); // Not a keymap changed event!
+    /// ```
+    pub fn is_keymap_changed(&self) -> bool {
+        matches!(self, Self::KeymapChanged { .. })
+    }
+
+    /// Returns `true` if this is a poll sentinel event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::event::Event;
+    ///
+    /// let ev = Event::PollSentinel {
+    ///     timestamp: 0,
+    /// };
+    /// assert!(ev.is_poll_sentinel());
     ///
     /// let another_ev = Event::Quit {
     ///     timestamp: 0,
     /// };
-    /// assert!(another_ev.is_controller() == false); // Not a controller event!
+    /// assert!(another_ev.is_poll_sentinel() == false); // Not a poll sentinel event!
     /// ```
-    pub fn is_controller(&self) -> bool {
-        matches!(
-            self,
-            Self::ControllerAxisMotion { .. }
-                | Self::ControllerButtonDown { .. }
-                | Self::ControllerButtonUp { .. }
-                | Self::ControllerDeviceAdded { .. }
-                | Self::ControllerDeviceRemoved { .. }
-                | Self::ControllerDeviceRemapped { .. }
-        )
+    pub fn is_poll_sentinel(&self) -> bool {
+        matches!(self, Self::PollSentinel { .. })
     }
 
-    /// Returns `true` if this is a joy event.
+    /// Returns `true` if this is a user event.
     ///
     /// # Example
     ///
     /// ```
     /// use sdl2::event::Event;
     ///
-    /// let ev = Event::JoyButtonUp {
+    /// let ev = Event::User {
     ///     timestamp: 0,
-    ///     which: 0,
-    ///     button_idx: 0,
+    ///     window_id: 0,
+    ///     type_: 0,
+    ///     code: 0,
+    ///     data1: ::std::ptr::null_mut(),
+    ///     data2: ::std::ptr::null_mut(),
     /// };
-    /// assert!(ev.is_joy());
+    /// assert!(ev.is_user());
     ///
     /// let another_ev = Event::Quit {
     ///     timestamp: 0,
     /// };
-    /// assert!(another_ev.is_joy() == false); // Not a joy event!
+    /// assert!(another_ev.is_user() == false); // Not a user event!
     /// ```
-    pub fn is_joy(&self) -> bool {
-        matches!(
-            self,
-            Self::JoyAxisMotion { .. }
-                | Self::JoyBallMotion { .. }
-                | Self::JoyHatMotion { .. }
-                | Self::JoyButtonDown { .. }
-                | Self::JoyButtonUp { .. }
-                | Self::JoyDeviceAdded { .. }
-                | Self::JoyDeviceRemoved { .. }
-        )
+    pub fn is_user(&self) -> bool {
+        matches!(self, Self::User { .. })
     }
 
-    /// Returns `true` if this is a finger event.
+    /// Returns `true` if this is an unknown event.
     ///
     /// # Example
     ///
     /// ```
     /// use sdl2::event::Event;
     ///
-    /// let ev = Event::FingerMotion {
+    /// let ev = Event::Unknown {
     ///     timestamp: 0,
-    ///     touch_id: 0,
-    ///     finger_id: 0,
-    ///     x: 0.,
-    ///     y: 0.,
-    ///     dx: 0.,
-    ///     dy: 0.,
-    ///     pressure: 0.,
+    ///     type_: 0,
     /// };
-    /// assert!(ev.is_finger());
+    /// assert!(ev.is_unknown());
     ///
     /// let another_ev = Event::Quit {
     ///     timestamp: 0,
     /// };
-    /// assert!(another_ev.is_finger() == false); // Not a finger event!
+    /// assert!(another_ev.is_unknown() == false); // Not an unknown event!
     /// ```
-    pub fn is_finger(&self) -> bool {
-        matches!(
-            self,
-            Self::FingerDown { .. } | Self::FingerUp { .. } | Self::FingerMotion { .. }
-        )
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown { .. })
+    }
+}
+
+/// Frees the strings allocated by SDL for a raw event that has been removed from the queue.
+unsafe fn free_ll_event_strings(raw: &sys::SDL_Event) {
+    let raw_type = raw.type_;
+    if raw_type == SDL_EventType::SDL_DROPFILE as u32
+        || raw_type == SDL_EventType::SDL_DROPTEXT as u32
+    {
+        sys::SDL_free(raw.drop.file as *mut c_void);
+    } else if raw_type == SDL_EventType::SDL_TEXTEDITING_EXT as u32 {
+        sys::SDL_free(raw.editExt.text as *mut c_void);
+    }
+}
+
+/// Frees everything owned by a raw event that is dropped without ever being converted with
+/// `Event::from_ll`, including custom event payloads.
+unsafe fn free_ll_event_data(raw: &sys::SDL_Event) {
+    free_ll_event_strings(raw);
+    if raw.type_ >= SDL_EventType::SDL_USEREVENT as u32 {
+        let payload = CUSTOM_EVENT_TYPES
+            .lock()
+            .unwrap()
+            .remove_payload(raw.user.type_, raw.user.data2 as usize);
+        if let Some(payload) = payload {
+            payload.free();
+        }
+    }
+}
+
+unsafe fn poll_event() -> Option<Event> {
+    let mut raw = mem::MaybeUninit::uninit();
+    let has_pending = sys::SDL_PollEvent(raw.as_mut_ptr()) == 1;
+
+    if has_pending {
+        Some(Event::from_ll(raw.assume_init()))
+    } else {
+        None
+    }
+}
+
+unsafe fn wait_event() -> Event {
+    let mut raw = mem::MaybeUninit::uninit();
+    let success = sys::SDL_WaitEvent(raw.as_mut_ptr()) == 1;
+
+    if success {
+        Event::from_ll(raw.assume_init())
+    } else {
+        panic!("{}", get_error())
+    }
+}
+
+unsafe fn wait_event_timeout(timeout: u32) -> Option<Event> {
+    let mut raw = mem::MaybeUninit::uninit();
+    let success = sys::SDL_WaitEventTimeout(raw.as_mut_ptr(), timeout as c_int) == 1;
+
+    if success {
+        Some(Event::from_ll(raw.assume_init()))
+    } else {
+        None
+    }
+}
+
+impl crate::EventPump {
+    /// Query if an event type is enabled.
+    #[doc(alias = "SDL_EventState")]
+    pub fn is_event_enabled(&self, event_type: EventType) -> bool {
+        let result = unsafe { sys::SDL_EventState(event_type as u32, sys::SDL_QUERY) };
+
+        result != sys::SDL_DISABLE as u8
+    }
+
+    /// Enable an event type. Returns if the event type was enabled before the call.
+    #[doc(alias = "SDL_EventState")]
+    pub fn enable_event(&mut self, event_type: EventType) -> bool {
+        let result = unsafe { sys::SDL_EventState(event_type as u32, sys::SDL_ENABLE as c_int) };
+
+        result != sys::SDL_DISABLE as u8
+    }
+
+    /// Disable an event type. Returns if the event type was enabled before the call.
+    #[doc(alias = "SDL_EventState")]
+    pub fn disable_event(&mut self, event_type: EventType) -> bool {
+        let result = unsafe { sys::SDL_EventState(event_type as u32, sys::SDL_DISABLE as c_int) };
+
+        result != sys::SDL_DISABLE as u8
+    }
+
+    /// Polls for currently pending events.
+    ///
+    /// If no events are pending, `None` is returned.
+    pub fn poll_event(&mut self) -> Option<Event> {
+        unsafe { poll_event() }
+    }
+
+    /// Returns a polling iterator that calls `poll_event()`.
+    /// The iterator will terminate once there are no more pending events.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let sdl_context = sdl2::init().unwrap();
+    /// let mut event_pump = sdl_context.event_pump().unwrap();
+    ///
+    /// for event in event_pump.poll_iter() {
+    ///     use sdl2::event::Event;
+    ///     match event {
+    ///         Event::KeyDown {..} => { /*...*/ }
+    ///         _ => ()
+    ///     }
+    /// }
+    /// ```
+    pub fn poll_iter(&mut self) -> EventPollIterator {
+        EventPollIterator {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Pumps the event loop, gathering events from the input devices.
+    #[doc(alias = "SDL_PumpEvents")]
+    pub fn pump_events(&mut self) {
+        unsafe {
+            sys::SDL_PumpEvents();
+        };
+    }
+
+    /// Pumps the event loop, then records the keyboard and mouse state in `snapshot`.
+    ///
+    /// See [`InputSnapshot`](../input/struct.InputSnapshot.html) for further information.
+    pub fn pump_events_into(&mut self, snapshot: &mut crate::input::InputSnapshot) {
+        self.pump_events();
+        snapshot.update(self);
+    }
+
+    /// Waits indefinitely for the next available event.
+    pub fn wait_event(&mut self) -> Event {
+        unsafe { wait_event() }
+    }
+
+    /// Waits until the specified timeout (in milliseconds) for the next available event.
+    pub fn wait_event_timeout(&mut self, timeout: u32) -> Option<Event> {
+        unsafe { wait_event_timeout(timeout) }
+    }
+
+    /// Returns a waiting iterator that calls `wait_event()`.
+    ///
+    /// Note: The iterator will never terminate.
+    pub fn wait_iter(&mut self) -> EventWaitIterator {
+        EventWaitIterator {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a waiting iterator that calls `wait_event_timeout()`.
+    ///
+    /// Note: The iterator will never terminate, unless waiting for an event
+    /// exceeds the specified timeout.
+    pub fn wait_timeout_iter(&mut self, timeout: u32) -> EventWaitTimeoutIterator {
+        EventWaitTimeoutIterator {
+            _marker: PhantomData,
+            timeout,
+        }
+    }
+
+    #[inline]
+    pub fn keyboard_state(&self) -> crate::keyboard::KeyboardState {
+        crate::keyboard::KeyboardState::new(self)
+    }
+
+    #[inline]
+    pub fn mouse_state(&self) -> crate::mouse::MouseState {
+        crate::mouse::MouseState::new(self)
+    }
+
+    #[inline]
+    pub fn relative_mouse_state(&self) -> crate::mouse::RelativeMouseState {
+        crate::mouse::RelativeMouseState::new(self)
+    }
+}
+
+/// An iterator that calls `EventPump::poll_event()`.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct EventPollIterator<'a> {
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> Iterator for EventPollIterator<'a> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        unsafe { poll_event() }
+    }
+}
+
+/// An iterator that calls `EventPump::wait_event()`.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct EventWaitIterator<'a> {
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> Iterator for EventWaitIterator<'a> {
+    type Item = Event;
+    fn next(&mut self) -> Option<Event> {
+        unsafe { Some(wait_event()) }
+    }
+}
+
+/// An iterator that calls `EventPump::wait_event_timeout()`.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct EventWaitTimeoutIterator<'a> {
+    _marker: PhantomData<&'a ()>,
+    timeout: u32,
+}
+
+impl<'a> Iterator for EventWaitTimeoutIterator<'a> {
+    type Item = Event;
+    fn next(&mut self) -> Option<Event> {
+        unsafe { wait_event_timeout(self.timeout) }
     }
+}
 
-    /// Returns `true` if this is a dollar event.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use sdl2::event::Event;
-    ///
-    /// let ev = Event::DollarGesture {
-    ///     timestamp: 0,
-    ///     touch_id: 0,
-    ///     gesture_id: 0,
-    ///     num_fingers: 0,
-    ///     error: 0.,
-    ///     x: 0.,
-    ///     y: 0.,
-    /// };
-    /// assert!(ev.is_dollar());
-    ///
-    /// let another_ev = Event::Quit {
-    ///     timestamp: 0,
-    /// };
-    /// assert!(another_ev.is_dollar() == false); // Not a dollar event!
-    /// ```
-    pub fn is_dollar(&self) -> bool {
-        matches!(self, Self::DollarGesture { .. } | Self::DollarRecord { .. })
+/// A sendible type that can push events to the event queue.
+pub struct EventSender {
+    _priv: (),
+}
+
+impl EventSender {
+    /// Pushes an event to the event queue.
+    #[doc(alias = "SDL_PushEvent")]
+    pub fn push_event(&self, event: Event) -> Result<(), String> {
+        match event.to_ll() {
+            Some(mut raw_event) => {
+                let result = unsafe { sys::SDL_PushEvent(&mut raw_event) };
+                if result == 1 {
+                    Ok(())
+                } else {
+                    // If the event was dropped by an event filter (`result == 0`), the filter
+                    // has already freed it.
+                    if result < 0 {
+                        unsafe { free_ll_event_strings(&raw_event) };
+                    }
+                    Err(get_error())
+                }
+            }
+            None => Err("Cannot push unsupported event type to the queue".to_owned()),
+        }
     }
 
-    /// Returns `true` if this is a drop event.
-    ///
-    /// # Example
+    /// Push a custom event
     ///
-    /// ```
-    /// use sdl2::event::Event;
+    /// If the event type ``T`` was not registered using
+    /// [EventSubsystem::register_custom_event]
+    /// (../struct.EventSubsystem.html#method.register_custom_event),
+    /// this method will panic.
     ///
-    /// let ev = Event::DropBegin {
-    ///     timestamp: 0,
-    ///     window_id: 3,
-    /// };
-    /// assert!(ev.is_drop());
+    /// The payload is owned by the event until it is taken with [`Event::take_user_event`],
+    /// released with [`Event::discard_user_event`], or flushed from the queue. A received
+    /// event that is dropped without one of these keeps its payload alive until the end of
+    /// the program.
     ///
-    /// let another_ev = Event::Quit {
-    ///     timestamp: 0,
-    /// };
-    /// assert!(another_ev.is_drop() == false); // Not a drop event!
+    /// # Example: pushing and receiving a custom event
     /// ```
-    pub fn is_drop(&self) -> bool {
-        matches!(
-            self,
-            Self::DropFile { .. }
-                | Self::DropText { .. }
-                | Self::DropBegin { .. }
-                | Self::DropComplete { .. }
-        )
-    }
-
-    /// Returns `true` if this is an audio event.
+    /// struct SomeCustomEvent {
+    ///     a: i32
+    /// }
     ///
-    /// # Example
+    /// let sdl = sdl2::init().unwrap();
+    /// let ev = sdl.event().unwrap();
+    /// let mut ep = sdl.event_pump().unwrap();
     ///
-    /// ```
-    /// use sdl2::event::Event;
+    /// ev.register_custom_event::<SomeCustomEvent>().unwrap();
     ///
-    /// let ev = Event::AudioDeviceAdded {
-    ///     timestamp: 0,
-    ///     which: 3,
-    ///     iscapture: false,
-    /// };
-    /// assert!(ev.is_audio());
+    /// let event = SomeCustomEvent { a: 42 };
     ///
-    /// let another_ev = Event::Quit {
-    ///     timestamp: 0,
-    /// };
-    /// assert!(another_ev.is_audio() == false); // Not an audio event!
+    /// ev.push_custom_event(event);
+    ///
+    /// let received = ep.poll_event().unwrap(); // or within a for event in ep.poll_iter()
+    /// if let Ok(e2) = received.take_user_event::<SomeCustomEvent>() {
+    ///     assert_eq!(e2.payload().a, 42);
+    /// }
     /// ```
-    pub fn is_audio(&self) -> bool {
-        matches!(
-            self,
-            Self::AudioDeviceAdded { .. } | Self::AudioDeviceRemoved { .. }
-        )
+    pub fn push_custom_event<T: ::std::any::Any + Send>(&self, event: T) -> Result<(), String> {
+        self.push_custom_event_impl(event, None)
     }
 
-    /// Returns `true` if this is a render event.
+    /// Push a custom event, returning a future that resolves once the event has been
+    /// received.
     ///
-    /// # Example
+    /// The future resolves to `Ok(())` once the event is removed from the event queue by the
+    /// application, typically by the thread running the `EventPump` with
+    /// [`EventPump::poll_event`](../struct.EventPump.html#method.poll_event) and alike, or once
+    /// its payload is taken with [`Event::take_user_event`] from a peeked copy. It resolves to
+    /// an error if the event is discarded before that (e.g. by
+    /// [`EventSubsystem::flush_custom_event`](../struct.EventSubsystem.html#method.flush_custom_event)
+    /// or an event filter).
     ///
-    /// ```
-    /// use sdl2::event::Event;
+    /// If the event type ``T`` was not registered using
+    /// [EventSubsystem::register_custom_event]
+    /// (../struct.EventSubsystem.html#method.register_custom_event),
+    /// this method will return an error.
     ///
-    /// let ev = Event::RenderTargetsReset {
-    ///     timestamp: 0,
-    /// };
-    /// assert!(ev.is_render());
+    /// # Example
+    /// ```no_run
+    /// struct Reload;
     ///
-    /// let another_ev = Event::Quit {
-    ///     timestamp: 0,
-    /// };
-    /// assert!(another_ev.is_render() == false); // Not a render event!
+    /// let sdl = sdl2::init().unwrap();
+    /// let ev = sdl.event().unwrap();
+    /// ev.register_custom_event::<Reload>().unwrap();
+    ///
+    /// let sender = ev.event_sender();
+    /// std::thread::spawn(move || {
+    ///     let received = sender.push_custom_event_received(Reload).unwrap();
+    ///     // e.g. `received.await` from an async task
+    /// #   drop(received);
+    /// });
     /// ```
-    pub fn is_render(&self) -> bool {
-        matches!(
-            self,
-            Self::RenderTargetsReset { .. } | Self::RenderDeviceReset { .. }
-        )
+    pub fn push_custom_event_received<T: ::std::any::Any + Send>(
+        &self,
+        event: T,
+    ) -> Result<CustomEventReceived, String> {
+        let state = Arc::new(Mutex::new(ReceivedState {
+            received: None,
+            waker: None,
+        }));
+        let notify = state.clone();
+        self.push_custom_event_impl(
+            event,
+            Some(Box::new(move |received| {
+                let waker = {
+                    let mut state = notify.lock().unwrap();
+                    state.received = Some(received);
+                    state.waker.take()
+                };
+                // Woken without holding the lock, as the waker may poll the future right away.
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            })),
+        )?;
+        Ok(CustomEventReceived { state })
+    }
+
+    fn push_custom_event_impl<T: ::std::any::Any + Send>(
+        &self,
+        event: T,
+        on_received: Option<Box<dyn FnOnce(bool) + Send>>,
+    ) -> Result<(), String> {
+        use std::any::TypeId;
+        let mut cet = CUSTOM_EVENT_TYPES.lock().unwrap();
+        let type_id = TypeId::of::<Box<T>>();
+
+        let user_event_id = *match cet.type_id_to_sdl_id.get(&type_id) {
+            Some(id) => id,
+            None => {
+                return Err("Type is not registered as a custom event type!".to_owned());
+            }
+        };
+
+        let event_ptr = Box::into_raw(Box::new(event)) as *mut c_void;
+        let token = cet.next_token;
+        cet.next_token += 1;
+        cet.payloads.insert(
+            token,
+            CustomEventPayload {
+                sdl_id: user_event_id,
+                ptr: event_ptr as usize,
+                drop_fn: drop_custom_event_payload::<T>,
+                on_received,
+            },
+        );
+        let event = Event::User {
+            timestamp: 0,
+            window_id: 0,
+            type_: user_event_id,
+            code: 0,
+            data1: event_ptr,
+            data2: token as *mut c_void,
+        };
+        drop(cet);
+
+        // The event never made it to the queue (e.g. it was dropped by an event filter),
+        // so nobody else can take its payload.
+        if let Err(e) = self.push_event(event) {
+            let payload = CUSTOM_EVENT_TYPES
+                .lock()
+                .unwrap()
+                .remove_payload(user_event_id, token);
+            if let Some(payload) = payload {
+                payload.free();
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+}
+
+struct ReceivedState {
+    received: Option<bool>,
+    waker: Option<Waker>,
+}
+
+/// A future that resolves once a custom event has been received.
+/// For further information, see [`EventSender::push_custom_event_received`].
+pub struct CustomEventReceived {
+    state: Arc<Mutex<ReceivedState>>,
+}
+
+impl std::future::Future for CustomEventReceived {
+    type Output = Result<(), String>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.received {
+            Some(true) => Poll::Ready(Ok(())),
+            Some(false) => Poll::Ready(Err(
+                "Custom event was discarded before being received".to_owned()
+            )),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// A stream of the events added to the event queue.
+/// For further information, see [`EventSubsystem::event_stream`].
+#[cfg(feature = "futures")]
+pub struct EventStream {
+    // Boxed so that its address, given to SDL, stays the same when the stream is moved.
+    shared: Box<Mutex<EventStreamState>>,
+}
+
+/// The maximum number of events buffered by an `EventStream`.
+#[cfg(feature = "futures")]
+const EVENT_STREAM_CAPACITY: usize = 1024;
+
+#[cfg(feature = "futures")]
+struct EventStreamState {
+    events: VecDeque<Event>,
+    waker: Option<Waker>,
+}
+
+#[cfg(feature = "futures")]
+impl EventStream {
+    fn add() -> EventStream {
+        let stream = EventStream {
+            shared: Box::new(Mutex::new(EventStreamState {
+                events: VecDeque::new(),
+                waker: None,
+            })),
+        };
+        unsafe {
+            sys::SDL_AddEventWatch(
+                Some(event_stream_marshall as _),
+                &*stream.shared as *const _ as *mut c_void,
+            )
+        };
+        stream
+    }
+
+    /// Removes and returns the next pending event without waiting.
+    pub fn try_next(&mut self) -> Option<Event> {
+        self.shared.lock().unwrap().events.pop_front()
+    }
+}
+
+#[cfg(feature = "futures")]
+impl Stream for EventStream {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        let mut state = self.shared.lock().unwrap();
+        match state.events.pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        unsafe {
+            sys::SDL_DelEventWatch(
+                Some(event_stream_marshall as _),
+                &*self.shared as *const _ as *mut c_void,
+            )
+        };
+    }
+}
+
+#[cfg(feature = "futures")]
+extern "C" fn event_stream_marshall(user_data: *mut c_void, event: *mut sys::SDL_Event) -> i32 {
+    let shared: &Mutex<EventStreamState> = unsafe { &*(user_data as *const _) };
+    let event = Event::from_ll_borrowed(unsafe { &*event });
+    if event.is_poll_sentinel() {
+        return 0;
+    }
+    let waker = match shared.lock() {
+        Ok(mut state) => {
+            if state.events.len() == EVENT_STREAM_CAPACITY {
+                state.events.pop_front();
+            }
+            state.events.push_back(event);
+            state.waker.take()
+        }
+        Err(_) => None,
+    };
+    // Woken without holding the lock, as the waker may poll the stream right away.
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+    0
+}
+
+/// A callback trait for [`EventSubsystem::add_event_watch`].
+pub trait EventWatchCallback {
+    fn callback(&mut self, event: Event);
+}
+
+/// An handler for the event watch callback.
+/// One must bind this struct in a variable as long as you want to keep the callback active.
+/// For further information, see [`EventSubsystem::add_event_watch`].
+pub struct EventWatch<'a, CB: EventWatchCallback + 'a> {
+    activated: bool,
+    callback: Box<CB>,
+    _phantom: PhantomData<&'a CB>,
+}
+
+impl<'a, CB: EventWatchCallback + 'a> EventWatch<'a, CB> {
+    fn add(callback: CB) -> EventWatch<'a, CB> {
+        let f = Box::new(callback);
+        let mut watch = EventWatch {
+            activated: false,
+            callback: f,
+            _phantom: PhantomData,
+        };
+        watch.activate();
+        watch
+    }
+
+    /// Activates the event watch.
+    /// Does nothing if it is already activated.
+    pub fn activate(&mut self) {
+        if !self.activated {
+            self.activated = true;
+            unsafe { sys::SDL_AddEventWatch(self.filter(), self.callback()) };
+        }
+    }
+
+    /// Deactivates the event watch.
+    /// Does nothing if it is already activated.
+    pub fn deactivate(&mut self) {
+        if self.activated {
+            self.activated = false;
+            unsafe { sys::SDL_DelEventWatch(self.filter(), self.callback()) };
+        }
+    }
+
+    /// Returns if the event watch is activated.
+    pub fn activated(&self) -> bool {
+        self.activated
     }
 
-    /// Returns `true` if this is a user event.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use sdl2::event::Event;
-    ///
-    /// let ev = Event::User {
-    ///     timestamp: 0,
-    ///     window_id: 0,
-    ///     type_: 0,
-    ///     code: 0,
-    ///     data1: ::std::ptr::null_mut(),
-    ///     data2: ::std::ptr::null_mut(),
-    /// };
-    /// assert!(ev.is_user());
-    ///
-    /// let another_ev = Event::Quit {
-    ///     timestamp: 0,
-    /// };
-    /// assert!(another_ev.is_user() == false); // Not a user event!
-    /// ```
-    pub fn is_user(&self) -> bool {
-        matches!(self, Self::User { .. })
+    /// Set the activation state of the event watch.
+    pub fn set_activated(&mut self, activate: bool) {
+        if activate {
+            self.activate();
+        } else {
+            self.deactivate();
+        }
     }
 
-    /// Returns `true` if this is an unknown event.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use sdl2::event::Event;
-    ///
-    /// let ev = Event::Unknown {
-    ///     timestamp: 0,
-    ///     type_: 0,
-    /// };
-    /// assert!(ev.is_unknown());
-    ///
-    /// let another_ev = Event::Quit {
-    ///     timestamp: 0,
-    /// };
-    /// assert!(another_ev.is_unknown() == false); // Not an unknown event!
-    /// ```
-    pub fn is_unknown(&self) -> bool {
-        matches!(self, Self::Unknown { .. })
+    fn filter(&self) -> SDL_EventFilter {
+        Some(event_callback_marshall::<CB> as _)
     }
-}
-
-unsafe fn poll_event() -> Option<Event> {
-    let mut raw = mem::MaybeUninit::uninit();
-    let has_pending = sys::SDL_PollEvent(raw.as_mut_ptr()) == 1;
 
-    if has_pending {
-        Some(Event::from_ll(raw.assume_init()))
-    } else {
-        None
+    fn callback(&mut self) -> *mut c_void {
+        &mut *self.callback as *mut _ as *mut c_void
     }
 }
 
-unsafe fn wait_event() -> Event {
-    let mut raw = mem::MaybeUninit::uninit();
-    let success = sys::SDL_WaitEvent(raw.as_mut_ptr()) == 1;
-
-    if success {
-        Event::from_ll(raw.assume_init())
-    } else {
-        panic!("{}", get_error())
+impl<'a, CB: EventWatchCallback + 'a> Drop for EventWatch<'a, CB> {
+    fn drop(&mut self) {
+        self.deactivate();
     }
 }
 
-unsafe fn wait_event_timeout(timeout: u32) -> Option<Event> {
-    let mut raw = mem::MaybeUninit::uninit();
-    let success = sys::SDL_WaitEventTimeout(raw.as_mut_ptr(), timeout as c_int) == 1;
+extern "C" fn event_callback_marshall<CB: EventWatchCallback>(
+    user_data: *mut c_void,
+    event: *mut sdl2_sys::SDL_Event,
+) -> i32 {
+    let f: &mut CB = unsafe { &mut *(user_data as *mut _) };
+    let event = Event::from_ll_borrowed(unsafe { &*event });
+    f.callback(event);
+    0
+}
 
-    if success {
-        Some(Event::from_ll(raw.assume_init()))
-    } else {
-        None
+impl<F: FnMut(Event)> EventWatchCallback for F {
+    fn callback(&mut self, event: Event) {
+        self(event)
     }
 }
 
-impl crate::EventPump {
-    /// Query if an event type is enabled.
-    #[doc(alias = "SDL_EventState")]
-    pub fn is_event_enabled(&self, event_type: EventType) -> bool {
-        let result = unsafe { sys::SDL_EventState(event_type as u32, sys::SDL_QUERY) };
+/// A callback trait for [`EventSubsystem::set_event_filter`] and
+/// [`EventSubsystem::filter_queued_events`].
+pub trait EventFilterCallback {
+    /// Returns `false` if the event should be dropped.
+    fn filter(&mut self, event: &Event) -> bool;
+}
 
-        result != sys::SDL_DISABLE as u8
-    }
+/// An handler for the event filter callback.
+/// One must bind this struct in a variable as long as you want to keep the filter active.
+/// For further information, see [`EventSubsystem::set_event_filter`].
+pub struct EventFilter<'a, CB: EventFilterCallback + 'a> {
+    callback: Box<CB>,
+    generation: u64,
+    _phantom: PhantomData<&'a CB>,
+}
 
-    /// Enable an event type. Returns if the event type was enabled before the call.
-    #[doc(alias = "SDL_EventState")]
-    pub fn enable_event(&mut self, event_type: EventType) -> bool {
-        let result = unsafe { sys::SDL_EventState(event_type as u32, sys::SDL_ENABLE as c_int) };
+/// Bumped every time an `EventFilter` is set. The userdata pointer SDL reports is not enough
+/// to tell filters apart, as boxed zero-sized closures all share one address.
+static EVENT_FILTER_GENERATION: AtomicU64 = AtomicU64::new(0);
 
-        result != sys::SDL_DISABLE as u8
+impl<'a, CB: EventFilterCallback + 'a> EventFilter<'a, CB> {
+    fn set(callback: CB) -> EventFilter<'a, CB> {
+        let mut filter = EventFilter {
+            callback: Box::new(callback),
+            generation: EVENT_FILTER_GENERATION.fetch_add(1, Ordering::SeqCst) + 1,
+            _phantom: PhantomData,
+        };
+        unsafe { sys::SDL_SetEventFilter(filter.filter(), filter.callback()) };
+        filter
     }
 
-    /// Disable an event type. Returns if the event type was enabled before the call.
-    #[doc(alias = "SDL_EventState")]
-    pub fn disable_event(&mut self, event_type: EventType) -> bool {
-        let result = unsafe { sys::SDL_EventState(event_type as u32, sys::SDL_DISABLE as c_int) };
+    /// Returns if this filter is still the active event filter, i.e. it has not been replaced
+    /// by another call to `EventSubsystem::set_event_filter`.
+    #[doc(alias = "SDL_GetEventFilter")]
+    pub fn is_active(&self) -> bool {
+        let mut filter: SDL_EventFilter = None;
+        let mut userdata = ptr::null_mut();
+        let has_filter = unsafe { sys::SDL_GetEventFilter(&mut filter, &mut userdata) };
+
+        has_filter == sys::SDL_bool::SDL_TRUE
+            && userdata as *const CB == &*self.callback
+            && EVENT_FILTER_GENERATION.load(Ordering::SeqCst) == self.generation
+    }
 
-        result != sys::SDL_DISABLE as u8
+    fn filter(&self) -> SDL_EventFilter {
+        Some(event_filter_marshall::<CB> as _)
     }
 
-    /// Polls for currently pending events.
-    ///
-    /// If no events are pending, `None` is returned.
-    pub fn poll_event(&mut self) -> Option<Event> {
-        unsafe { poll_event() }
+    fn callback(&mut self) -> *mut c_void {
+        &mut *self.callback as *mut _ as *mut c_void
     }
+}
 
-    /// Returns a polling iterator that calls `poll_event()`.
-    /// The iterator will terminate once there are no more pending events.
-    ///
-    /// # Example
-    /// ```no_run
-    /// let sdl_context = sdl2::init().unwrap();
-    /// let mut event_pump = sdl_context.event_pump().unwrap();
-    ///
-    /// for event in event_pump.poll_iter() {
-    ///     use sdl2::event::Event;
-    ///     match event {
-    ///         Event::KeyDown {..} => { /*...*/ }
-    ///         _ => ()
-    ///     }
-    /// }
-    /// ```
-    pub fn poll_iter(&mut self) -> EventPollIterator {
-        EventPollIterator {
-            _marker: PhantomData,
+impl<'a, CB: EventFilterCallback + 'a> Drop for EventFilter<'a, CB> {
+    fn drop(&mut self) {
+        // Do not remove a filter that has replaced this one.
+        if self.is_active() {
+            unsafe { sys::SDL_SetEventFilter(None, ptr::null_mut()) };
         }
     }
+}
 
-    /// Pumps the event loop, gathering events from the input devices.
-    #[doc(alias = "SDL_PumpEvents")]
-    pub fn pump_events(&mut self) {
-        unsafe {
-            sys::SDL_PumpEvents();
-        };
+extern "C" fn event_filter_marshall<CB: EventFilterCallback>(
+    user_data: *mut c_void,
+    event: *mut sdl2_sys::SDL_Event,
+) -> i32 {
+    let f: &mut CB = unsafe { &mut *(user_data as *mut _) };
+    let raw = unsafe { &*event };
+    if f.filter(&Event::from_ll_borrowed(raw)) {
+        1
+    } else {
+        // SDL discards the event without freeing what it points to.
+        unsafe { free_ll_event_data(raw) };
+        0
     }
+}
 
-    /// Waits indefinitely for the next available event.
-    pub fn wait_event(&mut self) -> Event {
-        unsafe { wait_event() }
+impl<F: FnMut(&Event) -> bool> EventFilterCallback for F {
+    fn filter(&mut self, event: &Event) -> bool {
+        self(event)
     }
+}
 
-    /// Waits until the specified timeout (in milliseconds) for the next available event.
-    pub fn wait_event_timeout(&mut self, timeout: u32) -> Option<Event> {
-        unsafe { wait_event_timeout(timeout) }
-    }
+/// Identifies a stream written by [`EventRecorder`].
+const EVENT_RECORDING_MAGIC: &[u8; 8] = b"SDL2EVTS";
+const EVENT_RECORDING_VERSION: u32 = 2;
 
-    /// Returns a waiting iterator that calls `wait_event()`.
-    ///
-    /// Note: The iterator will never terminate.
-    pub fn wait_iter(&mut self) -> EventWaitIterator {
-        EventWaitIterator {
-            _marker: PhantomData,
+/// A field of a raw event, stored in little-endian byte order in event recordings.
+trait RecordedField: Sized {
+    fn put(&self, buf: &mut Vec<u8>);
+    fn get(buf: &mut &[u8]) -> io::Result<Self>;
+}
+
+macro_rules! impl_recorded_field {
+    ($($ty:ty),*) => {$(
+        impl RecordedField for $ty {
+            fn put(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn get(buf: &mut &[u8]) -> io::Result<Self> {
+                let bytes = take_recorded_bytes(buf, mem::size_of::<$ty>())?;
+                Ok(<$ty>::from_le_bytes(bytes.try_into().unwrap()))
+            }
         }
-    }
+    )*};
+}
 
-    /// Returns a waiting iterator that calls `wait_event_timeout()`.
-    ///
-    /// Note: The iterator will never terminate, unless waiting for an event
-    /// exceeds the specified timeout.
-    pub fn wait_timeout_iter(&mut self, timeout: u32) -> EventWaitTimeoutIterator {
-        EventWaitTimeoutIterator {
-            _marker: PhantomData,
-            timeout,
+impl_recorded_field!(u8, u16, u32, u64, i16, i32, i64, f32);
+
+impl RecordedField for [f32; 3] {
+    fn put(&self, buf: &mut Vec<u8>) {
+        for value in self {
+            value.put(buf);
         }
     }
 
-    #[inline]
-    pub fn keyboard_state(&self) -> crate::keyboard::KeyboardState {
-        crate::keyboard::KeyboardState::new(self)
+    fn get(buf: &mut &[u8]) -> io::Result<Self> {
+        Ok([f32::get(buf)?, f32::get(buf)?, f32::get(buf)?])
     }
+}
 
-    #[inline]
-    pub fn mouse_state(&self) -> crate::mouse::MouseState {
-        crate::mouse::MouseState::new(self)
+/// The fixed-size, nul-terminated text of `SDL_TextEditingEvent` and `SDL_TextInputEvent`,
+/// stored without its trailing nul bytes.
+impl RecordedField for [c_char; 32] {
+    fn put(&self, buf: &mut Vec<u8>) {
+        let len = self.iter().position(|&c| c == 0).unwrap_or(self.len());
+        (len as u8).put(buf);
+        buf.extend(self[..len].iter().map(|&c| c as u8));
     }
 
-    #[inline]
-    pub fn relative_mouse_state(&self) -> crate::mouse::RelativeMouseState {
-        crate::mouse::RelativeMouseState::new(self)
+    fn get(buf: &mut &[u8]) -> io::Result<Self> {
+        let len = u8::get(buf)? as usize;
+        let mut text = [0; 32];
+        if len >= text.len() {
+            return Err(invalid_recording("Text too long in event recording"));
+        }
+        let bytes = take_recorded_bytes(buf, len)?;
+        for (c, &byte) in text.iter_mut().zip(bytes) {
+            *c = byte as c_char;
+        }
+        Ok(text)
     }
 }
 
-/// An iterator that calls `EventPump::poll_event()`.
-#[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct EventPollIterator<'a> {
-    _marker: PhantomData<&'a ()>,
+fn invalid_recording(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_owned())
 }
 
-impl<'a> Iterator for EventPollIterator<'a> {
-    type Item = Event;
-
-    fn next(&mut self) -> Option<Event> {
-        unsafe { poll_event() }
+fn take_recorded_bytes<'a>(buf: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(invalid_recording("Truncated event in event recording"));
     }
+    let (bytes, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(bytes)
 }
 
-/// An iterator that calls `EventPump::wait_event()`.
-#[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct EventWaitIterator<'a> {
-    _marker: PhantomData<&'a ()>,
+/// Stores a string owned by a raw event, which must not be null.
+unsafe fn put_recorded_str(buf: &mut Vec<u8>, s: *const c_char) {
+    let bytes = CStr::from_ptr(s).to_bytes();
+    (bytes.len() as u32).put(buf);
+    buf.extend_from_slice(bytes);
 }
 
-impl<'a> Iterator for EventWaitIterator<'a> {
-    type Item = Event;
-    fn next(&mut self) -> Option<Event> {
-        unsafe { Some(wait_event()) }
-    }
+fn get_recorded_str(buf: &mut &[u8]) -> io::Result<CString> {
+    let len = u32::get(buf)? as usize;
+    let bytes = take_recorded_bytes(buf, len)?;
+    CString::new(bytes).map_err(|e| invalid_recording(&e.to_string()))
 }
 
-/// An iterator that calls `EventPump::wait_event_timeout()`.
-#[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct EventWaitTimeoutIterator<'a> {
-    _marker: PhantomData<&'a ()>,
-    timeout: u32,
+/// Stores the fields of a raw event that are meaningful outside of this process, skipping
+/// padding and pointers.
+unsafe fn put_ll_event(buf: &mut Vec<u8>, raw: &sys::SDL_Event) {
+    macro_rules! put {
+        ($member:ident: $($field:ident),*) => {{
+            let event = raw.$member;
+            $(event.$field.put(buf);)*
+        }};
+    }
+
+    let raw_type = raw.common.type_;
+    raw_type.put(buf);
+    raw.common.timestamp.put(buf);
+    if raw_type >= SDL_EventType::SDL_USEREVENT as u32 {
+        put!(user: windowID, code);
+        return;
+    }
+
+    match EventType::try_from(raw_type) {
+        Ok(EventType::Display) => put!(display: display, event, data1),
+        Ok(EventType::Window) => put!(window: windowID, event, data1, data2),
+        Ok(EventType::KeyDown) | Ok(EventType::KeyUp) => {
+            put!(key: windowID, state, repeat);
+            let keysym = raw.key.keysym;
+            (keysym.scancode as u32).put(buf);
+            keysym.sym.put(buf);
+            keysym.mod_.put(buf);
+        }
+        Ok(EventType::TextEditing) => put!(edit: windowID, text, start, length),
+        Ok(EventType::TextInput) => put!(text: windowID, text),
+        Ok(EventType::TextEditingExt) => {
+            put!(editExt: windowID, start, length);
+            put_recorded_str(buf, raw.editExt.text);
+        }
+        Ok(EventType::MouseMotion) => put!(motion: windowID, which, state, x, y, xrel, yrel),
+        Ok(EventType::MouseButtonDown) | Ok(EventType::MouseButtonUp) => {
+            put!(button: windowID, which, button, state, clicks, x, y)
+        }
+        Ok(EventType::MouseWheel) => put!(
+            wheel: windowID,
+            which,
+            x,
+            y,
+            direction,
+            preciseX,
+            preciseY,
+            mouseX,
+            mouseY
+        ),
+        Ok(EventType::JoyAxisMotion) => put!(jaxis: which, axis, value),
+        Ok(EventType::JoyBallMotion) => put!(jball: which, ball, xrel, yrel),
+        Ok(EventType::JoyHatMotion) => put!(jhat: which, hat, value),
+        Ok(EventType::JoyButtonDown) | Ok(EventType::JoyButtonUp) => {
+            put!(jbutton: which, button, state)
+        }
+        Ok(EventType::JoyDeviceAdded) | Ok(EventType::JoyDeviceRemoved) => put!(jdevice: which),
+        Ok(EventType::ControllerAxisMotion) => put!(caxis: which, axis, value),
+        Ok(EventType::ControllerButtonDown) | Ok(EventType::ControllerButtonUp) => {
+            put!(cbutton: which, button, state)
+        }
+        Ok(EventType::ControllerDeviceAdded)
+        | Ok(EventType::ControllerDeviceRemoved)
+        | Ok(EventType::ControllerDeviceRemapped) => put!(cdevice: which),
+        Ok(EventType::ControllerTouchpadDown)
+        | Ok(EventType::ControllerTouchpadMotion)
+        | Ok(EventType::ControllerTouchpadUp) => {
+            put!(ctouchpad: which, touchpad, finger, x, y, pressure)
+        }
+        #[cfg(feature = "hidapi")]
+        Ok(EventType::ControllerSensorUpdated) => {
+            put!(csensor: which, sensor, data, timestamp_us)
+        }
+        Ok(EventType::FingerDown) | Ok(EventType::FingerUp) | Ok(EventType::FingerMotion) => {
+            put!(tfinger: touchId, fingerId, x, y, dx, dy, pressure, windowID)
+        }
+        Ok(EventType::DollarGesture) | Ok(EventType::DollarRecord) => {
+            put!(dgesture: touchId, gestureId, numFingers, error, x, y)
+        }
+        Ok(EventType::MultiGesture) => put!(mgesture: touchId, dTheta, dDist, x, y, numFingers),
+        Ok(EventType::DropFile) | Ok(EventType::DropText) => {
+            put!(drop: windowID);
+            put_recorded_str(buf, raw.drop.file);
+        }
+        Ok(EventType::DropBegin) | Ok(EventType::DropComplete) => put!(drop: windowID),
+        Ok(EventType::AudioDeviceAdded) | Ok(EventType::AudioDeviceRemoved) => {
+            put!(adevice: which, iscapture)
+        }
+        // Only the type and timestamp are meaningful.
+        _ => (),
+    }
 }
 
-impl<'a> Iterator for EventWaitTimeoutIterator<'a> {
-    type Item = Event;
-    fn next(&mut self) -> Option<Event> {
-        unsafe { wait_event_timeout(self.timeout) }
+/// Reads back a raw event stored by `put_ll_event`, along with the string it points to, if
+/// any.
+fn get_ll_event(buf: &mut &[u8]) -> io::Result<(sys::SDL_Event, Option<CString>)> {
+    let mut raw: sys::SDL_Event = unsafe { mem::zeroed() };
+    let mut string = None;
+    macro_rules! get {
+        ($member:ident: $($field:ident),*) => {{
+            let mut event = unsafe { raw.$member };
+            $(event.$field = RecordedField::get(buf)?;)*
+            raw.$member = event;
+        }};
+    }
+
+    let raw_type = u32::get(buf)?;
+    let timestamp = u32::get(buf)?;
+    raw.common = sys::SDL_CommonEvent {
+        type_: raw_type,
+        timestamp,
+    };
+    if raw_type >= SDL_EventType::SDL_USEREVENT as u32 {
+        if raw_type == SDL_EventType::SDL_LASTEVENT as u32 {
+            return Err(invalid_recording("Invalid event type in event recording"));
+        }
+        get!(user: windowID, code);
+        return Ok((raw, None));
+    }
+
+    match EventType::try_from(raw_type) {
+        Ok(EventType::First) => {
+            return Err(invalid_recording("Invalid event type in event recording"));
+        }
+        Ok(EventType::Display) => get!(display: display, event, data1),
+        Ok(EventType::Window) => get!(window: windowID, event, data1, data2),
+        Ok(EventType::KeyDown) | Ok(EventType::KeyUp) => {
+            get!(key: windowID, state, repeat);
+            let scancode = Scancode::from_i32(u32::get(buf)? as i32)
+                .map(|sc| unsafe { transmute::<u32, sys::SDL_Scancode>(sc as u32) })
+                .unwrap_or(sys::SDL_Scancode::SDL_SCANCODE_UNKNOWN);
+            raw.key.keysym = sys::SDL_Keysym {
+                scancode,
+                sym: i32::get(buf)?,
+                mod_: u16::get(buf)?,
+                unused: 0,
+            };
+        }
+        Ok(EventType::TextEditing) => get!(edit: windowID, text, start, length),
+        Ok(EventType::TextInput) => get!(text: windowID, text),
+        Ok(EventType::TextEditingExt) => {
+            get!(editExt: windowID, start, length);
+            let text = get_recorded_str(buf)?;
+            raw.editExt.text = text.as_ptr() as *mut c_char;
+            string = Some(text);
+        }
+        Ok(EventType::MouseMotion) => get!(motion: windowID, which, state, x, y, xrel, yrel),
+        Ok(EventType::MouseButtonDown) | Ok(EventType::MouseButtonUp) => {
+            get!(button: windowID, which, button, state, clicks, x, y)
+        }
+        Ok(EventType::MouseWheel) => get!(
+            wheel: windowID,
+            which,
+            x,
+            y,
+            direction,
+            preciseX,
+            preciseY,
+            mouseX,
+            mouseY
+        ),
+        Ok(EventType::JoyAxisMotion) => get!(jaxis: which, axis, value),
+        Ok(EventType::JoyBallMotion) => get!(jball: which, ball, xrel, yrel),
+        Ok(EventType::JoyHatMotion) => get!(jhat: which, hat, value),
+        Ok(EventType::JoyButtonDown) | Ok(EventType::JoyButtonUp) => {
+            get!(jbutton: which, button, state)
+        }
+        Ok(EventType::JoyDeviceAdded) | Ok(EventType::JoyDeviceRemoved) => get!(jdevice: which),
+        Ok(EventType::ControllerAxisMotion) => get!(caxis: which, axis, value),
+        Ok(EventType::ControllerButtonDown) | Ok(EventType::ControllerButtonUp) => {
+            get!(cbutton: which, button, state)
+        }
+        Ok(EventType::ControllerDeviceAdded)
+        | Ok(EventType::ControllerDeviceRemoved)
+        | Ok(EventType::ControllerDeviceRemapped) => get!(cdevice: which),
+        Ok(EventType::ControllerTouchpadDown)
+        | Ok(EventType::ControllerTouchpadMotion)
+        | Ok(EventType::ControllerTouchpadUp) => {
+            get!(ctouchpad: which, touchpad, finger, x, y, pressure)
+        }
+        #[cfg(feature = "hidapi")]
+        Ok(EventType::ControllerSensorUpdated) => {
+            get!(csensor: which, sensor, data, timestamp_us)
+        }
+        Ok(EventType::FingerDown) | Ok(EventType::FingerUp) | Ok(EventType::FingerMotion) => {
+            get!(tfinger: touchId, fingerId, x, y, dx, dy, pressure, windowID)
+        }
+        Ok(EventType::DollarGesture) | Ok(EventType::DollarRecord) => {
+            get!(dgesture: touchId, gestureId, numFingers, error, x, y)
+        }
+        Ok(EventType::MultiGesture) => get!(mgesture: touchId, dTheta, dDist, x, y, numFingers),
+        Ok(EventType::DropFile) | Ok(EventType::DropText) => {
+            get!(drop: windowID);
+            let file = get_recorded_str(buf)?;
+            raw.drop.file = file.as_ptr() as *mut c_char;
+            string = Some(file);
+        }
+        Ok(EventType::DropBegin) | Ok(EventType::DropComplete) => get!(drop: windowID),
+        Ok(EventType::AudioDeviceAdded) | Ok(EventType::AudioDeviceRemoved) => {
+            get!(adevice: which, iscapture)
+        }
+        _ => (),
     }
+    Ok((raw, string))
 }
 
-/// A sendible type that can push events to the event queue.
-pub struct EventSender {
-    _priv: (),
+/// Records events into a compact binary stream, to be replayed later with [`EventReplayer`].
+///
+/// Any `io::Write` can be used as output, including [`RWops`](../rwops/struct.RWops.html).
+/// Each event is stored field by field along with its timestamp, in little-endian byte
+/// order, so a recording can be replayed on any platform.
+///
+/// The `data1` and `data2` pointers of `Event::User` are meaningless outside of the process
+/// which pushed them, so they are not recorded and are replayed as null pointers. In
+/// particular, the payloads of custom events are not recorded.
+///
+/// # Example
+/// ```no_run
+/// use sdl2::event::EventRecorder;
+/// use sdl2::rwops::RWops;
+///
+/// let sdl_context = sdl2::init().unwrap();
+/// let mut event_pump = sdl_context.event_pump().unwrap();
+///
+/// let file = RWops::from_file("input.rec", "wb").unwrap();
+/// let mut recorder = EventRecorder::new(file).unwrap();
+///
+/// for event in event_pump.poll_iter() {
+///     recorder.record(&event).unwrap();
+///     // ...
+/// }
+/// ```
+pub struct EventRecorder<W: io::Write> {
+    writer: W,
+    buf: Vec<u8>,
 }
 
-impl EventSender {
-    /// Pushes an event to the event queue.
-    #[doc(alias = "SDL_PushEvent")]
-    pub fn push_event(&self, event: Event) -> Result<(), String> {
-        match event.to_ll() {
-            Some(mut raw_event) => {
-                let ok = unsafe { sys::SDL_PushEvent(&mut raw_event) == 1 };
-                if ok {
-                    Ok(())
-                } else {
-                    Err(get_error())
-                }
-            }
-            None => Err("Cannot push unsupported event type to the queue".to_owned()),
-        }
+impl<W: io::Write> EventRecorder<W> {
+    /// Starts a recording by writing its header to `writer`.
+    pub fn new(mut writer: W) -> io::Result<EventRecorder<W>> {
+        writer.write_all(EVENT_RECORDING_MAGIC)?;
+        writer.write_all(&EVENT_RECORDING_VERSION.to_le_bytes())?;
+        Ok(EventRecorder {
+            writer,
+            buf: Vec::new(),
+        })
     }
 
-    /// Push a custom event
-    ///
-    /// If the event type ``T`` was not registered using
-    /// [EventSubsystem::register_custom_event]
-    /// (../struct.EventSubsystem.html#method.register_custom_event),
-    /// this method will panic.
-    ///
-    /// # Example: pushing and receiving a custom event
-    /// ```
-    /// struct SomeCustomEvent {
-    ///     a: i32
-    /// }
-    ///
-    /// let sdl = sdl2::init().unwrap();
-    /// let ev = sdl.event().unwrap();
-    /// let mut ep = sdl.event_pump().unwrap();
+    /// Appends an event to the recording.
     ///
-    /// ev.register_custom_event::<SomeCustomEvent>().unwrap();
-    ///
-    /// let event = SomeCustomEvent { a: 42 };
-    ///
-    /// ev.push_custom_event(event);
-    ///
-    /// let received = ep.poll_event().unwrap(); // or within a for event in ep.poll_iter()
-    /// if received.is_user_event() {
-    ///     let e2 = received.as_user_event_type::<SomeCustomEvent>().unwrap();
-    ///     assert_eq!(e2.a, 42);
-    /// }
-    /// ```
-    pub fn push_custom_event<T: ::std::any::Any>(&self, event: T) -> Result<(), String> {
-        use std::any::TypeId;
-        let cet = CUSTOM_EVENT_TYPES.lock().unwrap();
-        let type_id = TypeId::of::<Box<T>>();
-
-        let user_event_id = *match cet.type_id_to_sdl_id.get(&type_id) {
-            Some(id) => id,
-            None => {
-                return Err("Type is not registered as a custom event type!".to_owned());
-            }
-        };
-
-        let event_box = Box::new(event);
-        let event = Event::User {
-            timestamp: 0,
-            window_id: 0,
-            type_: user_event_id,
-            code: 0,
-            data1: Box::into_raw(event_box) as *mut c_void,
-            data2: ::std::ptr::null_mut(),
-        };
-        drop(cet);
+    /// Returns an error of kind `InvalidInput` if the event cannot be converted to an
+    /// `SDL_Event`, e.g. a `TextInput` event whose text is too long.
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        let raw = event.to_ll().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot record unsupported event type",
+            )
+        })?;
+
+        self.buf.clear();
+        unsafe {
+            put_ll_event(&mut self.buf, &raw);
+            free_ll_event_strings(&raw);
+        }
+        self.writer
+            .write_all(&(self.buf.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&self.buf)
+    }
 
-        self.push_event(event)?;
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
 
-        Ok(())
+    /// Ends the recording, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
     }
 }
 
-/// A callback trait for [`EventSubsystem::add_event_watch`].
-pub trait EventWatchCallback {
-    fn callback(&mut self, event: Event);
+/// How [`EventReplayer::replay`] paces the replayed events.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ReplayTiming {
+    /// Wait between events as long as elapsed between them when they were recorded.
+    Original,
+    /// Push all the events without waiting.
+    AsFastAsPossible,
 }
 
-/// An handler for the event watch callback.
-/// One must bind this struct in a variable as long as you want to keep the callback active.
-/// For further information, see [`EventSubsystem::add_event_watch`].
-pub struct EventWatch<'a, CB: EventWatchCallback + 'a> {
-    activated: bool,
-    callback: Box<CB>,
-    _phantom: PhantomData<&'a CB>,
+/// Reads back the events recorded by an [`EventRecorder`].
+///
+/// Events can either be read one by one, as an iterator, or be pushed back to the event
+/// queue with [`replay`](#method.replay).
+///
+/// # Example
+/// ```no_run
+/// use sdl2::event::{EventReplayer, ReplayTiming};
+/// use sdl2::rwops::RWops;
+///
+/// let sdl_context = sdl2::init().unwrap();
+/// let event_subsystem = sdl_context.event().unwrap();
+/// let sender = event_subsystem.event_sender();
+///
+/// std::thread::spawn(move || {
+///     let file = RWops::from_file("input.rec", "rb").unwrap();
+///     let mut replayer = EventReplayer::new(file).unwrap();
+///     replayer.replay(&sender, ReplayTiming::Original).unwrap();
+/// });
+/// ```
+pub struct EventReplayer<R: io::Read> {
+    reader: R,
 }
 
-impl<'a, CB: EventWatchCallback + 'a> EventWatch<'a, CB> {
-    fn add(callback: CB) -> EventWatch<'a, CB> {
-        let f = Box::new(callback);
-        let mut watch = EventWatch {
-            activated: false,
-            callback: f,
-            _phantom: PhantomData,
-        };
-        watch.activate();
-        watch
-    }
+impl<R: io::Read> EventReplayer<R> {
+    /// Reads and checks the header of a recording.
+    ///
+    /// Returns an error of kind `InvalidData` if `reader` does not contain a recording that
+    /// can be replayed by this version of the library.
+    pub fn new(mut reader: R) -> io::Result<EventReplayer<R>> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != EVENT_RECORDING_MAGIC {
+            return Err(invalid_recording("Not an event recording"));
+        }
 
-    /// Activates the event watch.
-    /// Does nothing if it is already activated.
-    pub fn activate(&mut self) {
-        if !self.activated {
-            self.activated = true;
-            unsafe { sys::SDL_AddEventWatch(self.filter(), self.callback()) };
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        if u32::from_le_bytes(version) != EVENT_RECORDING_VERSION {
+            return Err(invalid_recording("Unsupported event recording version"));
         }
+
+        Ok(EventReplayer { reader })
     }
 
-    /// Deactivates the event watch.
-    /// Does nothing if it is already activated.
-    pub fn deactivate(&mut self) {
-        if self.activated {
-            self.activated = false;
-            unsafe { sys::SDL_DelEventWatch(self.filter(), self.callback()) };
+    /// Reads the next recorded event, or returns `None` at the end of the recording.
+    pub fn read_event(&mut self) -> io::Result<Option<Event>> {
+        // A clean end of the recording is only allowed between two events.
+        let mut len = [0; 4];
+        let read = read_fully(&mut self.reader, &mut len)?;
+        if read == 0 {
+            return Ok(None);
+        } else if read < len.len() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
         }
-    }
 
-    /// Returns if the event watch is activated.
-    pub fn activated(&self) -> bool {
-        self.activated
-    }
+        // Read through `take` rather than allocating the length up front, as it may be bogus.
+        let len = u32::from_le_bytes(len) as usize;
+        let mut record = Vec::new();
+        self.reader
+            .by_ref()
+            .take(len as u64)
+            .read_to_end(&mut record)?;
+        if record.len() < len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
 
-    /// Set the activation state of the event watch.
-    pub fn set_activated(&mut self, activate: bool) {
-        if activate {
-            self.activate();
-        } else {
-            self.deactivate();
+        let mut fields = &record[..];
+        // `_string` owns the text the raw event points to, if any.
+        let (raw, _string) = get_ll_event(&mut fields)?;
+        if !fields.is_empty() {
+            return Err(invalid_recording("Unexpected data in event recording"));
         }
-    }
 
-    fn filter(&self) -> SDL_EventFilter {
-        Some(event_callback_marshall::<CB> as _)
+        Ok(Some(Event::from_ll_borrowed(&raw)))
     }
 
-    fn callback(&mut self) -> *mut c_void {
-        &mut *self.callback as *mut _ as *mut c_void
+    /// Pushes every remaining event of the recording to the event queue through `sender`,
+    /// returning the number of replayed events.
+    ///
+    /// With `ReplayTiming::Original`, this blocks the current thread between events for as
+    /// long as elapsed between them when they were recorded, so it is best called from
+    /// another thread than the one running the event loop.
+    ///
+    /// Note that SDL sets the timestamp of the pushed events to the time they are pushed at.
+    pub fn replay(&mut self, sender: &EventSender, timing: ReplayTiming) -> Result<usize, String> {
+        let mut previous_timestamp = None;
+        let mut count = 0;
+
+        while let Some(event) = self.read_event().map_err(|e| format!("IO error: {}", e))? {
+            let timestamp = event.get_timestamp();
+            if let (ReplayTiming::Original, Some(previous)) = (timing, previous_timestamp) {
+                let delay = u64::from(timestamp.saturating_sub(previous));
+                std::thread::sleep(std::time::Duration::from_millis(delay));
+            }
+            previous_timestamp = Some(timestamp);
+
+            sender.push_event(event)?;
+            count += 1;
+        }
+
+        Ok(count)
     }
-}
 
-impl<'a, CB: EventWatchCallback + 'a> Drop for EventWatch<'a, CB> {
-    fn drop(&mut self) {
-        self.deactivate();
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
     }
 }
 
-extern "C" fn event_callback_marshall<CB: EventWatchCallback>(
-    user_data: *mut c_void,
-    event: *mut sdl2_sys::SDL_Event,
-) -> i32 {
-    let f: &mut CB = unsafe { &mut *(user_data as *mut _) };
-    let event = Event::from_ll(unsafe { *event });
-    f.callback(event);
-    0
+impl<R: io::Read> Iterator for EventReplayer<R> {
+    type Item = io::Result<Event>;
+
+    fn next(&mut self) -> Option<io::Result<Event>> {
+        self.read_event().transpose()
+    }
 }
 
-impl<F: FnMut(Event)> EventWatchCallback for F {
-    fn callback(&mut self, event: Event) {
-        self(event)
+/// Reads until `buf` is full or the end of the stream is reached, returning the number of
+/// bytes read.
+fn read_fully<R: io::Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
     }
+    Ok(read)
 }
 
 #[cfg(test)]
@@ -2983,6 +5168,11 @@ mod test {
     use super::DisplayEvent;
     use super::Event;
     use super::WindowEvent;
+    use super::{EventRecorder, EventReplayer};
+    use crate::sys;
+    use libc::c_void;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
     // Tests a round-trip conversion from an Event type to
     // the SDL event type and back, to make sure it's sane.
@@ -3210,6 +5400,497 @@ mod test {
         }
     }
 
+    fn random_text<R: Rng>(rng: &mut R) -> String {
+        const CHARS: &[char] = &['a', 'Z', '0', ' ', 'é', 'ß', '€', '日', '😀'];
+        let mut text = String::new();
+        loop {
+            let c = CHARS[rng.gen_range(0, CHARS.len())];
+            // Leave room for the nul terminator
+            if text.len() + c.len_utf8() >= 32 || rng.gen_range(0, 16) == 0 {
+                return text;
+            }
+            text.push(c);
+        }
+    }
+
+    /// Returns one random event of each variant that can be converted to an `SDL_Event`.
+    fn random_events<R: Rng>(rng: &mut R) -> Vec<Event> {
+        const WINDOW_EVENTS: &[WindowEvent] = &[
+            WindowEvent::None,
+            WindowEvent::Shown,
+            WindowEvent::Moved(-4, 8),
+            WindowEvent::SizeChanged(640, 480),
+            WindowEvent::FocusLost,
+            WindowEvent::ICCProfChanged,
+            WindowEvent::DisplayChanged(2),
+        ];
+        const DISPLAY_EVENTS: &[DisplayEvent] = &[
+            DisplayEvent::None,
+            DisplayEvent::Orientation(Orientation::Unknown),
+            DisplayEvent::Orientation(Orientation::PortraitFlipped),
+            DisplayEvent::Connected,
+            DisplayEvent::Disconnected,
+        ];
+        const MOUSE_BUTTONS: &[MouseButton] = &[
+            MouseButton::Unknown,
+            MouseButton::Left,
+            MouseButton::Middle,
+            MouseButton::Right,
+            MouseButton::X1,
+            MouseButton::X2,
+        ];
+        const HAT_STATES: &[HatState] = &[
+            HatState::Centered,
+            HatState::Up,
+            HatState::RightDown,
+            HatState::LeftUp,
+        ];
+        const AXES: &[Axis] = &[Axis::LeftX, Axis::RightY, Axis::TriggerRight];
+        const BUTTONS: &[Button] = &[Button::A, Button::Guide, Button::DPadLeft, Button::Touchpad];
+
+        let t: u32 = rng.gen();
+        let window_id: u32 = rng.gen();
+        let which: u32 = rng.gen_range(0, i32::MAX as u32);
+        let keycode = Keycode::from_i32(rng.gen());
+        let scancode = Scancode::from_i32(rng.gen_range(4, 100));
+        let keymod = Mod::from_bits_truncate(rng.gen());
+
+        vec![
+            Event::Quit { timestamp: t },
+            Event::AppTerminating { timestamp: t },
+            Event::AppLowMemory { timestamp: t },
+            Event::AppWillEnterBackground { timestamp: t },
+            Event::AppDidEnterBackground { timestamp: t },
+            Event::AppWillEnterForeground { timestamp: t },
+            Event::AppDidEnterForeground { timestamp: t },
+            Event::Display {
+                timestamp: t,
+                display_index: rng.gen_range(0, 8),
+                display_event: DISPLAY_EVENTS[rng.gen_range(0, DISPLAY_EVENTS.len())],
+            },
+            Event::Window {
+                timestamp: t,
+                window_id,
+                win_event: WINDOW_EVENTS[rng.gen_range(0, WINDOW_EVENTS.len())],
+            },
+            Event::KeyDown {
+                timestamp: t,
+                window_id,
+                keycode,
+                scancode,
+                keymod,
+                repeat: rng.gen(),
+            },
+            Event::KeyUp {
+                timestamp: t,
+                window_id,
+                keycode,
+                scancode,
+                keymod,
+                repeat: rng.gen(),
+            },
+            Event::TextEditing {
+                timestamp: t,
+                window_id,
+                text: random_text(rng),
+                start: rng.gen(),
+                length: rng.gen(),
+            },
+            Event::TextInput {
+                timestamp: t,
+                window_id,
+                text: random_text(rng),
+            },
+            Event::MouseMotion {
+                timestamp: t,
+                window_id,
+                which,
+                mousestate: MouseState::from_sdl_state(rng.gen_range(0, 32)),
+                x: rng.gen(),
+                y: rng.gen(),
+                xrel: rng.gen(),
+                yrel: rng.gen(),
+            },
+            Event::MouseButtonDown {
+                timestamp: t,
+                window_id,
+                which,
+                mouse_btn: MOUSE_BUTTONS[rng.gen_range(0, MOUSE_BUTTONS.len())],
+                clicks: rng.gen(),
+                x: rng.gen(),
+                y: rng.gen(),
+            },
+            Event::MouseButtonUp {
+                timestamp: t,
+                window_id,
+                which,
+                mouse_btn: MOUSE_BUTTONS[rng.gen_range(0, MOUSE_BUTTONS.len())],
+                clicks: rng.gen(),
+                x: rng.gen(),
+                y: rng.gen(),
+            },
+            Event::MouseWheel {
+                timestamp: t,
+                window_id,
+                which,
+                x: rng.gen(),
+                y: rng.gen(),
+                direction: MouseWheelDirection::from_ll(rng.gen_range(0, 3)),
+                precise_x: rng.gen(),
+                precise_y: rng.gen(),
+                mouse_x: rng.gen(),
+                mouse_y: rng.gen(),
+            },
+            Event::JoyAxisMotion {
+                timestamp: t,
+                which,
+                axis_idx: rng.gen(),
+                value: rng.gen(),
+            },
+            Event::JoyBallMotion {
+                timestamp: t,
+                which,
+                ball_idx: rng.gen(),
+                xrel: rng.gen(),
+                yrel: rng.gen(),
+            },
+            Event::JoyHatMotion {
+                timestamp: t,
+                which,
+                hat_idx: rng.gen(),
+                state: HAT_STATES[rng.gen_range(0, HAT_STATES.len())],
+            },
+            Event::JoyButtonDown {
+                timestamp: t,
+                which,
+                button_idx: rng.gen(),
+            },
+            Event::JoyButtonUp {
+                timestamp: t,
+                which,
+                button_idx: rng.gen(),
+            },
+            Event::JoyDeviceAdded {
+                timestamp: t,
+                which,
+            },
+            Event::JoyDeviceRemoved {
+                timestamp: t,
+                which,
+            },
+            Event::ControllerAxisMotion {
+                timestamp: t,
+                which,
+                axis: AXES[rng.gen_range(0, AXES.len())],
+                value: rng.gen(),
+            },
+            Event::ControllerButtonDown {
+                timestamp: t,
+                which,
+                button: BUTTONS[rng.gen_range(0, BUTTONS.len())],
+            },
+            Event::ControllerButtonUp {
+                timestamp: t,
+                which,
+                button: BUTTONS[rng.gen_range(0, BUTTONS.len())],
+            },
+            Event::ControllerDeviceAdded {
+                timestamp: t,
+                which,
+            },
+            Event::ControllerDeviceRemoved {
+                timestamp: t,
+                which,
+            },
+            Event::ControllerDeviceRemapped {
+                timestamp: t,
+                which,
+            },
+            Event::ControllerTouchpadDown {
+                timestamp: t,
+                which,
+                touchpad: rng.gen_range(0, 4),
+                finger: rng.gen_range(0, 10),
+                x: rng.gen(),
+                y: rng.gen(),
+                pressure: rng.gen(),
+            },
+            Event::ControllerTouchpadMotion {
+                timestamp: t,
+                which,
+                touchpad: rng.gen_range(0, 4),
+                finger: rng.gen_range(0, 10),
+                x: rng.gen(),
+                y: rng.gen(),
+                pressure: rng.gen(),
+            },
+            Event::ControllerTouchpadUp {
+                timestamp: t,
+                which,
+                touchpad: rng.gen_range(0, 4),
+                finger: rng.gen_range(0, 10),
+                x: rng.gen(),
+                y: rng.gen(),
+                pressure: rng.gen(),
+            },
+            #[cfg(feature = "hidapi")]
+            Event::ControllerSensorUpdated {
+                timestamp: t,
+                which,
+                sensor: crate::sensor::SensorType::Gyroscope,
+                data: [rng.gen(), rng.gen(), rng.gen()],
+            },
+            Event::FingerDown {
+                timestamp: t,
+                touch_id: rng.gen(),
+                finger_id: rng.gen(),
+                x: rng.gen(),
+                y: rng.gen(),
+                dx: rng.gen(),
+                dy: rng.gen(),
+                pressure: rng.gen(),
+            },
+            Event::FingerUp {
+                timestamp: t,
+                touch_id: rng.gen(),
+                finger_id: rng.gen(),
+                x: rng.gen(),
+                y: rng.gen(),
+                dx: rng.gen(),
+                dy: rng.gen(),
+                pressure: rng.gen(),
+            },
+            Event::FingerMotion {
+                timestamp: t,
+                touch_id: rng.gen(),
+                finger_id: rng.gen(),
+                x: rng.gen(),
+                y: rng.gen(),
+                dx: rng.gen(),
+                dy: rng.gen(),
+                pressure: rng.gen(),
+            },
+            Event::DollarGesture {
+                timestamp: t,
+                touch_id: rng.gen(),
+                gesture_id: rng.gen(),
+                num_fingers: rng.gen(),
+                error: rng.gen(),
+                x: rng.gen(),
+                y: rng.gen(),
+            },
+            Event::DollarRecord {
+                timestamp: t,
+                touch_id: rng.gen(),
+                gesture_id: rng.gen(),
+                num_fingers: rng.gen(),
+                error: rng.gen(),
+                x: rng.gen(),
+                y: rng.gen(),
+            },
+            Event::MultiGesture {
+                timestamp: t,
+                touch_id: rng.gen(),
+                d_theta: rng.gen(),
+                d_dist: rng.gen(),
+                x: rng.gen(),
+                y: rng.gen(),
+                num_fingers: rng.gen(),
+            },
+            Event::ClipboardUpdate { timestamp: t },
+            Event::LocaleChanged { timestamp: t },
+            Event::KeymapChanged { timestamp: t },
+            Event::TextEditingExt {
+                timestamp: t,
+                window_id,
+                text: random_text(rng) + &random_text(rng) + &random_text(rng),
+                start: rng.gen(),
+                length: rng.gen(),
+            },
+            Event::PollSentinel { timestamp: t },
+            Event::DropFile {
+                timestamp: t,
+                window_id,
+                filename: random_text(rng) + &random_text(rng),
+            },
+            Event::DropText {
+                timestamp: t,
+                window_id,
+                filename: random_text(rng),
+            },
+            Event::DropBegin {
+                timestamp: t,
+                window_id,
+            },
+            Event::DropComplete {
+                timestamp: t,
+                window_id,
+            },
+            Event::AudioDeviceAdded {
+                timestamp: t,
+                which: rng.gen(),
+                iscapture: rng.gen(),
+            },
+            Event::AudioDeviceRemoved {
+                timestamp: t,
+                which: rng.gen(),
+                iscapture: rng.gen(),
+            },
+            Event::RenderTargetsReset { timestamp: t },
+            Event::RenderDeviceReset { timestamp: t },
+            Event::User {
+                timestamp: t,
+                window_id,
+                type_: rng.gen_range(0x8000, 0xFFFF),
+                code: rng.gen(),
+                data1: rng.gen::<usize>() as *mut c_void,
+                data2: rng.gen::<usize>() as *mut c_void,
+            },
+            Event::Unknown {
+                timestamp: t,
+                // Below SDL_POLLSENTINEL (0x7F00), the last built-in event type
+                type_: rng.gen_range(0x3000, 0x7F00),
+            },
+        ]
+    }
+
+    // Property test: every event that can be represented both ways survives a round-trip.
+    #[test]
+    fn test_to_from_ll_random() {
+        let mut rng = StdRng::seed_from_u64(0x5d1_2e7e);
+        for _ in 0..200 {
+            for e in random_events(&mut rng) {
+                let raw = e
+                    .to_ll()
+                    .unwrap_or_else(|| panic!("cannot convert {:?}", e));
+                assert_eq!(e, Event::from_ll(raw));
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_ll_unrepresentable() {
+        let too_long = Event::TextInput {
+            timestamp: 0,
+            window_id: 0,
+            text: "a".repeat(32),
+        };
+        assert!(too_long.to_ll().is_none());
+
+        let user_with_builtin_type = Event::User {
+            timestamp: 0,
+            window_id: 0,
+            type_: sys::SDL_EventType::SDL_DROPFILE as u32,
+            code: 0,
+            data1: ::std::ptr::null_mut(),
+            data2: ::std::ptr::null_mut(),
+        };
+        assert!(user_with_builtin_type.to_ll().is_none());
+
+        let unknown_with_builtin_type = Event::Unknown {
+            timestamp: 0,
+            type_: sys::SDL_EventType::SDL_DROPFILE as u32,
+        };
+        assert!(unknown_with_builtin_type.to_ll().is_none());
+    }
+
+    #[test]
+    fn test_record_replay() {
+        let events = vec![
+            Event::KeyDown {
+                timestamp: 10,
+                window_id: 1,
+                keycode: Some(Keycode::A),
+                scancode: Some(Scancode::A),
+                keymod: Mod::LSHIFTMOD,
+                repeat: false,
+            },
+            Event::TextInput {
+                timestamp: 11,
+                window_id: 1,
+                text: "A".to_owned(),
+            },
+            Event::DropFile {
+                timestamp: 20,
+                window_id: 1,
+                filename: "/tmp/some file.txt".to_owned(),
+            },
+            Event::User {
+                timestamp: 30,
+                window_id: 0,
+                type_: 0x8001,
+                code: 7,
+                data1: 0x1234 as *mut c_void,
+                data2: 0x5678 as *mut c_void,
+            },
+        ];
+
+        let mut recorder = EventRecorder::new(Vec::new()).unwrap();
+        for event in &events {
+            recorder.record(event).unwrap();
+        }
+        let recording = recorder.into_inner();
+
+        let replayed: Vec<Event> = EventReplayer::new(&recording[..])
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(replayed[..3], events[..3]);
+        // User data pointers are not recorded
+        assert_eq!(
+            replayed[3],
+            Event::User {
+                timestamp: 30,
+                window_id: 0,
+                type_: 0x8001,
+                code: 7,
+                data1: ::std::ptr::null_mut(),
+                data2: ::std::ptr::null_mut(),
+            }
+        );
+
+        assert!(EventReplayer::new(&recording[..4]).is_err());
+        let mut truncated = EventReplayer::new(&recording[..recording.len() - 1]).unwrap();
+        assert!(truncated.nth(3).unwrap().is_err());
+    }
+
+    #[test]
+    fn test_record_replay_random() {
+        let mut rng = StdRng::seed_from_u64(0x2ec_04d5);
+        let mut recorder = EventRecorder::new(Vec::new()).unwrap();
+        let mut events = Vec::new();
+        for _ in 0..50 {
+            for e in random_events(&mut rng) {
+                recorder.record(&e).unwrap();
+                events.push(match e {
+                    // User data pointers are not recorded
+                    Event::User {
+                        timestamp,
+                        window_id,
+                        type_,
+                        code,
+                        ..
+                    } => Event::User {
+                        timestamp,
+                        window_id,
+                        type_,
+                        code,
+                        data1: ::std::ptr::null_mut(),
+                        data2: ::std::ptr::null_mut(),
+                    },
+                    e => e,
+                });
+            }
+        }
+        let recording = recorder.into_inner();
+
+        let replayed: Vec<Event> = EventReplayer::new(&recording[..])
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(replayed, events);
+    }
+
     #[test]
     fn test_from_ll_keymod_keydown_unknown_bits() {
         let mut raw_event = Event::KeyDown {
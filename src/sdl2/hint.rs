@@ -3,6 +3,7 @@ use libc::c_char;
 use std::ffi::{CStr, CString};
 
 const VIDEO_MINIMIZE_ON_FOCUS_LOSS: &str = "SDL_VIDEO_MINIMIZE_ON_FOCUS_LOSS";
+const IME_SUPPORT_EXTENDED_TEXT: &str = "SDL_IME_SUPPORT_EXTENDED_TEXT";
 
 pub enum Hint {
     Default,
@@ -74,6 +75,69 @@ pub fn get_video_minimize_on_focus_loss() -> bool {
     )
 }
 
+/// A hint that specifies whether text editing events longer than the fixed size buffer
+/// of [`Event::TextEditing`](../event/enum.Event.html#variant.TextEditing) are delivered
+/// as [`Event::TextEditingExt`](../event/enum.Event.html#variant.TextEditingExt) instead
+/// of being truncated.
+///
+/// [Official SDL documentation](https://wiki.libsdl.org/SDL_HINT_IME_SUPPORT_EXTENDED_TEXT)
+///
+/// # Default
+/// This is disabled by default.
+///
+/// # Example
+/// ```rust,no_run
+/// sdl2::hint::set_ime_support_extended_text(true);
+/// ```
+///
+/// * `value`: `true` to receive long composition text as `TextEditingExt` events, `false` to keep receiving truncated `TextEditing` events.
+pub fn set_ime_support_extended_text(value: bool) -> bool {
+    set(IME_SUPPORT_EXTENDED_TEXT, if value { "1" } else { "0" })
+}
+
+/// A hint that specifies whether text editing events longer than the fixed size buffer
+/// of [`Event::TextEditing`](../event/enum.Event.html#variant.TextEditing) are delivered
+/// as [`Event::TextEditingExt`](../event/enum.Event.html#variant.TextEditingExt) instead
+/// of being truncated.
+///
+/// [Official SDL documentation](https://wiki.libsdl.org/SDL_HINT_IME_SUPPORT_EXTENDED_TEXT)
+///
+/// # Example
+/// ```rust,no_run
+/// sdl2::hint::set_ime_support_extended_text_with_priority(true, &sdl2::hint::Hint::Override);
+/// ```
+///
+/// * `value`: `true` to receive long composition text as `TextEditingExt` events, `false` to keep receiving truncated `TextEditing` events.
+/// * `priority`: The priority controls the behavior when setting a hint that already has a value. Hints will replace existing hints of their priority and lower. Environment variables are considered to have override priority.
+pub fn set_ime_support_extended_text_with_priority(value: bool, priority: &Hint) -> bool {
+    set_with_priority(
+        IME_SUPPORT_EXTENDED_TEXT,
+        if value { "1" } else { "0" },
+        priority,
+    )
+}
+
+/// A hint that specifies whether text editing events longer than the fixed size buffer
+/// of [`Event::TextEditing`](../event/enum.Event.html#variant.TextEditing) are delivered
+/// as [`Event::TextEditingExt`](../event/enum.Event.html#variant.TextEditingExt) instead
+/// of being truncated.
+///
+/// [Official SDL documentation](https://wiki.libsdl.org/SDL_HINT_IME_SUPPORT_EXTENDED_TEXT)
+///
+/// # Default
+/// By default this will return `false`.
+///
+/// # Example
+/// ```rust,no_run
+/// assert_eq!(sdl2::hint::get_ime_support_extended_text(), false);
+///
+/// sdl2::hint::set_ime_support_extended_text(true);
+/// assert_eq!(sdl2::hint::get_ime_support_extended_text(), true);
+/// ```
+pub fn get_ime_support_extended_text() -> bool {
+    matches!(get(IME_SUPPORT_EXTENDED_TEXT).as_deref(), Some("1"))
+}
+
 #[doc(alias = "SDL_SetHint")]
 pub fn set(name: &str, value: &str) -> bool {
     let name = CString::new(name).unwrap();
@@ -0,0 +1,1018 @@
+//! Mapping of keyboard, mouse and game controller input to named actions, and per-frame
+//! tracking of the keyboard and mouse state.
+//!
+//! An [`ActionMap`] binds actions such as `"jump"` to one or more [`Binding`]s and tracks
+//! their state from the events of the event loop:
+//!
+//! ```no_run
+//! use sdl2::controller::Button;
+//! use sdl2::input::{ActionMap, Binding};
+//! use sdl2::keyboard::Keycode;
+//!
+//! let sdl = sdl2::init().unwrap();
+//! let mut event_pump = sdl.event_pump().unwrap();
+//!
+//! let mut actions = ActionMap::new();
+//! actions.bind("jump", Binding::key(Keycode::SPACE));
+//! actions.bind("jump", Binding::ControllerButton(Button::A));
+//!
+//! loop {
+//!     actions.begin_frame();
+//!     for event in event_pump.poll_iter() {
+//!         actions.handle_event(&event);
+//!     }
+//!
+//!     if actions.just_pressed("jump") {
+//!         println!("jump!");
+//!     }
+//! }
+//! ```
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+
+use crate::controller::{Axis, Button};
+use crate::event::Event;
+use crate::keyboard::{key_mod, KeyboardState, Keycode, Mod, Scancode};
+use crate::mouse::{MouseButton, MouseState};
+use crate::sys;
+use crate::EventPump;
+
+/// An input that can trigger an action.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Binding {
+    /// A key, identified by its virtual key code, pressed while holding `keymod`.
+    Key {
+        keycode: Keycode,
+        keymod: Mod,
+    },
+    /// A key, identified by its physical location, pressed while holding `keymod`.
+    Scancode {
+        scancode: Scancode,
+        keymod: Mod,
+    },
+    Mouse(MouseButton),
+    ControllerButton(Button),
+    /// A game controller axis pushed beyond `threshold`, between -1.0 and 1.0.
+    ///
+    /// A negative threshold matches the axis pushed in the negative direction.
+    ControllerAxis {
+        axis: Axis,
+        threshold: f32,
+    },
+}
+
+impl Binding {
+    /// A key binding without modifiers.
+    pub fn key(keycode: Keycode) -> Binding {
+        Binding::Key {
+            keycode,
+            keymod: Mod::NOMOD,
+        }
+    }
+
+    /// A scancode binding without modifiers.
+    pub fn scancode(scancode: Scancode) -> Binding {
+        Binding::Scancode {
+            scancode,
+            keymod: Mod::NOMOD,
+        }
+    }
+
+    /// Returns the binding matching an input event, e.g. to let the player press the input to
+    /// assign to an action.
+    ///
+    /// Key presses are turned into a `Key` binding with the held modifiers, excluding the
+    /// modifier of the pressed key itself. Controller axis motions are turned into a binding
+    /// with a threshold of 0.5 once they go past it.
+    pub fn from_event(event: &Event) -> Option<Binding> {
+        match *event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                repeat: false,
+                ..
+            } => Some(Binding::Key {
+                keycode,
+                keymod: keymod & !key_mod(keycode) & modifier_keys(),
+            }),
+            Event::MouseButtonDown { mouse_btn, .. } if mouse_btn != MouseButton::Unknown => {
+                Some(Binding::Mouse(mouse_btn))
+            }
+            Event::ControllerButtonDown { button, .. } => Some(Binding::ControllerButton(button)),
+            Event::ControllerAxisMotion { axis, value, .. } => {
+                let value = normalize_axis(value);
+                if value >= 0.5 {
+                    Some(Binding::ControllerAxis {
+                        axis,
+                        threshold: 0.5,
+                    })
+                } else if value <= -0.5 {
+                    Some(Binding::ControllerAxis {
+                        axis,
+                        threshold: -0.5,
+                    })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The text format of a binding, as used by [`ActionMap::save_bindings`]:
+///
+/// * `key:Space`, `key[ctrl,shift]:S`: a key by name with optional modifiers
+/// * `scancode:Space`, `scancode[lalt]:F4`: a scancode by name with optional modifiers
+/// * `mouse:left`: one of `left`, `middle`, `right`, `x1` or `x2`
+/// * `button:a`: a controller button by its mapping string name
+/// * `axis:lefty<-0.5`, `axis:triggerright>0.5`: a controller axis by its mapping string name
+///   with its threshold
+///
+/// The modifiers are `ctrl`, `shift`, `alt` and `gui` for either side, their one-sided
+/// versions prefixed with `l` or `r` (e.g. `lctrl`), and `num`, `caps` and `mode`.
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Binding::Key { keycode, keymod } => {
+                write!(f, "key{}:", ModNames(keymod))?;
+                let name = keycode.name();
+                if name.is_empty() {
+                    write!(f, "#{}", keycode.into_i32())
+                } else {
+                    write!(f, "{}", name)
+                }
+            }
+            Binding::Scancode { scancode, keymod } => {
+                write!(f, "scancode{}:", ModNames(keymod))?;
+                let name = scancode.name();
+                if name.is_empty() {
+                    write!(f, "#{}", scancode as i32)
+                } else {
+                    write!(f, "{}", name)
+                }
+            }
+            Binding::Mouse(button) => {
+                let name = match button {
+                    MouseButton::Left => "left",
+                    MouseButton::Middle => "middle",
+                    MouseButton::Right => "right",
+                    MouseButton::X1 => "x1",
+                    MouseButton::X2 => "x2",
+                    MouseButton::Unknown => "unknown",
+                };
+                write!(f, "mouse:{}", name)
+            }
+            Binding::ControllerButton(button) => write!(f, "button:{}", button.string()),
+            Binding::ControllerAxis { axis, threshold } => {
+                let op = if threshold < 0.0 { '<' } else { '>' };
+                write!(f, "axis:{}{}{}", axis.string(), op, threshold)
+            }
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Binding, String> {
+        let invalid = || format!("Invalid binding '{}'", s);
+
+        let colon = s.find(':').ok_or_else(invalid)?;
+        let (kind, name) = (&s[..colon], &s[colon + 1..]);
+        let (kind, keymod) = match kind.find('[') {
+            Some(bracket) if kind.ends_with(']') => (
+                &kind[..bracket],
+                parse_mods(&kind[bracket + 1..kind.len() - 1]).ok_or_else(invalid)?,
+            ),
+            Some(_) => return Err(invalid()),
+            None => (kind, Mod::NOMOD),
+        };
+        if keymod != Mod::NOMOD && kind != "key" && kind != "scancode" {
+            return Err(invalid());
+        }
+
+        let binding = match kind {
+            "key" => Binding::Key {
+                keycode: match parse_code(name) {
+                    Some(code) => Keycode::from_i32(code),
+                    None => Keycode::from_name(name),
+                }
+                .ok_or_else(invalid)?,
+                keymod,
+            },
+            "scancode" => Binding::Scancode {
+                scancode: match parse_code(name) {
+                    Some(code) => Scancode::from_i32(code),
+                    None => Scancode::from_name(name),
+                }
+                .ok_or_else(invalid)?,
+                keymod,
+            },
+            "mouse" => Binding::Mouse(match name {
+                "left" => MouseButton::Left,
+                "middle" => MouseButton::Middle,
+                "right" => MouseButton::Right,
+                "x1" => MouseButton::X1,
+                "x2" => MouseButton::X2,
+                _ => return Err(invalid()),
+            }),
+            "button" => Binding::ControllerButton(Button::from_string(name).ok_or_else(invalid)?),
+            "axis" => {
+                let op = name.find(['<', '>']).ok_or_else(invalid)?;
+                let axis = Axis::from_string(&name[..op]).ok_or_else(invalid)?;
+                let threshold: f32 = name[op + 1..].parse().map_err(|_| invalid())?;
+                let valid = match &name[op..op + 1] {
+                    "<" => (-1.0..0.0).contains(&threshold),
+                    _ => (0.0..=1.0).contains(&threshold),
+                };
+                if !valid {
+                    return Err(invalid());
+                }
+                Binding::ControllerAxis { axis, threshold }
+            }
+            _ => return Err(invalid()),
+        };
+        Ok(binding)
+    }
+}
+
+/// Parses the `#<code>` form used for keys without a name.
+fn parse_code(name: &str) -> Option<i32> {
+    if name.len() > 1 && name.starts_with('#') {
+        name[1..].parse().ok()
+    } else {
+        None
+    }
+}
+
+const MOD_NAMES: &[(&str, Mod)] = &[
+    ("lctrl", Mod::LCTRLMOD),
+    ("rctrl", Mod::RCTRLMOD),
+    ("lshift", Mod::LSHIFTMOD),
+    ("rshift", Mod::RSHIFTMOD),
+    ("lalt", Mod::LALTMOD),
+    ("ralt", Mod::RALTMOD),
+    ("lgui", Mod::LGUIMOD),
+    ("rgui", Mod::RGUIMOD),
+    ("num", Mod::NUMMOD),
+    ("caps", Mod::CAPSMOD),
+    ("mode", Mod::MODEMOD),
+];
+
+/// The modifiers that come in a left and a right version, with their name for either side.
+fn mod_pairs() -> [(&'static str, Mod); 4] {
+    [
+        ("ctrl", Mod::LCTRLMOD | Mod::RCTRLMOD),
+        ("shift", Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        ("alt", Mod::LALTMOD | Mod::RALTMOD),
+        ("gui", Mod::LGUIMOD | Mod::RGUIMOD),
+    ]
+}
+
+/// The modifiers set by holding a key, as opposed to the lock modifiers.
+fn modifier_keys() -> Mod {
+    mod_pairs()
+        .iter()
+        .fold(Mod::MODEMOD, |acc, &(_, pair)| acc | pair)
+}
+
+fn parse_mods(s: &str) -> Option<Mod> {
+    let mut keymod = Mod::NOMOD;
+    for name in s.split(',').map(str::trim) {
+        keymod |= mod_pairs()
+            .iter()
+            .chain(MOD_NAMES)
+            .find(|&&(n, _)| n == name)?
+            .1;
+    }
+    Some(keymod)
+}
+
+struct ModNames(Mod);
+
+impl fmt::Display for ModNames {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut keymod = self.0;
+        let mut names = Vec::new();
+        for &(name, pair) in mod_pairs().iter() {
+            if keymod.contains(pair) {
+                names.push(name);
+                keymod.remove(pair);
+            }
+        }
+        for &(name, m) in MOD_NAMES {
+            if keymod.contains(m) {
+                names.push(name);
+            }
+        }
+
+        if names.is_empty() {
+            Ok(())
+        } else {
+            write!(f, "[{}]", names.join(","))
+        }
+    }
+}
+
+/// Returns whether the held modifiers `current` satisfy the modifiers `required` by a binding.
+///
+/// Requiring both sides of a modifier (e.g. `LCTRLMOD | RCTRLMOD`) is satisfied by holding
+/// either of them.
+fn mods_match(required: Mod, current: Mod) -> bool {
+    let mut rest = required;
+    for &(_, pair) in mod_pairs().iter() {
+        let side = required & pair;
+        if !side.is_empty() && !current.intersects(side) {
+            return false;
+        }
+        rest.remove(pair);
+    }
+    current.contains(rest)
+}
+
+fn normalize_axis(value: i16) -> f32 {
+    (value as f32 / i16::MAX as f32).max(-1.0)
+}
+
+#[derive(Default)]
+struct ActionState {
+    bindings: Vec<Binding>,
+    pressed: bool,
+    just_pressed: bool,
+    just_released: bool,
+}
+
+/// Binds named actions to keyboard, mouse and game controller inputs, and tracks their state.
+///
+/// Feed every event to [`handle_event`](#method.handle_event) and call
+/// [`begin_frame`](#method.begin_frame) once per frame before handling the frame's events.
+/// Input from every connected game controller is taken into account.
+///
+/// See the [module documentation](index.html) for an example.
+pub struct ActionMap {
+    actions: BTreeMap<String, ActionState>,
+    keymod: Mod,
+    keycodes: HashSet<Keycode>,
+    scancodes: HashSet<Scancode>,
+    mouse_buttons: HashSet<MouseButton>,
+    /// Held buttons, by controller `which` id.
+    controller_buttons: HashSet<(u32, Button)>,
+    /// Axis values, by controller `which` id.
+    controller_axes: HashMap<(u32, Axis), f32>,
+}
+
+impl ActionMap {
+    pub fn new() -> ActionMap {
+        ActionMap {
+            actions: BTreeMap::new(),
+            keymod: Mod::NOMOD,
+            keycodes: HashSet::new(),
+            scancodes: HashSet::new(),
+            mouse_buttons: HashSet::new(),
+            controller_buttons: HashSet::new(),
+            controller_axes: HashMap::new(),
+        }
+    }
+
+    /// Adds a binding to `action`, creating the action if needed.
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        let state = self.actions.entry(action.to_owned()).or_default();
+        if !state.bindings.contains(&binding) {
+            state.bindings.push(binding);
+        }
+        self.update_actions();
+    }
+
+    /// Removes a binding from `action`. Returns `false` if it was not bound.
+    pub fn unbind(&mut self, action: &str, binding: &Binding) -> bool {
+        let removed = match self.actions.get_mut(action) {
+            Some(state) => {
+                let len = state.bindings.len();
+                state.bindings.retain(|b| b != binding);
+                state.bindings.len() != len
+            }
+            None => false,
+        };
+        self.update_actions();
+        removed
+    }
+
+    /// Replaces the bindings of `action`, creating the action if needed.
+    pub fn set_bindings(&mut self, action: &str, bindings: Vec<Binding>) {
+        self.actions.entry(action.to_owned()).or_default().bindings = bindings;
+        self.update_actions();
+    }
+
+    /// Returns the bindings of `action`, or an empty slice if there is no such action.
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions
+            .get(action)
+            .map_or(&[], |state| &state.bindings[..])
+    }
+
+    /// Returns the names of the actions, in alphabetical order.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|action| action.as_str())
+    }
+
+    /// Removes `action` and its bindings.
+    pub fn remove_action(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    /// Clears the `just_pressed` and `just_released` state of every action.
+    ///
+    /// Call this once per frame, before handling the frame's events.
+    pub fn begin_frame(&mut self) {
+        for state in self.actions.values_mut() {
+            state.just_pressed = false;
+            state.just_released = false;
+        }
+    }
+
+    /// Updates the input state from `event`. Events other than input events are ignored.
+    pub fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::KeyDown {
+                keycode,
+                scancode,
+                keymod,
+                ..
+            } => {
+                self.keymod = keymod;
+                if let Some(keycode) = keycode {
+                    self.keycodes.insert(keycode);
+                }
+                if let Some(scancode) = scancode {
+                    self.scancodes.insert(scancode);
+                }
+            }
+            Event::KeyUp {
+                keycode,
+                scancode,
+                keymod,
+                ..
+            } => {
+                self.keymod = keymod;
+                if let Some(keycode) = keycode {
+                    self.keycodes.remove(&keycode);
+                }
+                if let Some(scancode) = scancode {
+                    self.scancodes.remove(&scancode);
+                }
+            }
+            Event::MouseButtonDown { mouse_btn, .. } => {
+                self.mouse_buttons.insert(mouse_btn);
+            }
+            Event::MouseButtonUp { mouse_btn, .. } => {
+                self.mouse_buttons.remove(&mouse_btn);
+            }
+            Event::ControllerButtonDown { which, button, .. } => {
+                self.controller_buttons.insert((which, button));
+            }
+            Event::ControllerButtonUp { which, button, .. } => {
+                self.controller_buttons.remove(&(which, button));
+            }
+            Event::ControllerAxisMotion {
+                which, axis, value, ..
+            } => {
+                self.controller_axes
+                    .insert((which, axis), normalize_axis(value));
+            }
+            Event::ControllerDeviceRemoved { which, .. } => {
+                self.controller_buttons.retain(|&(w, _)| w != which);
+                self.controller_axes.retain(|&(w, _), _| w != which);
+            }
+            _ => return,
+        }
+        self.update_actions();
+    }
+
+    /// Returns `true` if any binding of `action` is currently held.
+    pub fn pressed(&self, action: &str) -> bool {
+        matches!(self.actions.get(action), Some(state) if state.pressed)
+    }
+
+    /// Returns `true` if `action` was pressed since the last call to
+    /// [`begin_frame`](#method.begin_frame).
+    ///
+    /// This is also `true` if the action was both pressed and released since then.
+    pub fn just_pressed(&self, action: &str) -> bool {
+        matches!(self.actions.get(action), Some(state) if state.just_pressed)
+    }
+
+    /// Returns `true` if `action` was released since the last call to
+    /// [`begin_frame`](#method.begin_frame).
+    pub fn just_released(&self, action: &str) -> bool {
+        matches!(self.actions.get(action), Some(state) if state.just_released)
+    }
+
+    /// Returns the analog value of `action`, between 0.0 and 1.0.
+    ///
+    /// Held digital bindings count as 1.0. Controller axis bindings count as how far the axis
+    /// is pushed in the direction of their threshold, or 0.0 while within it. The greatest
+    /// value of all bindings is returned.
+    pub fn value(&self, action: &str) -> f32 {
+        self.bindings(action)
+            .iter()
+            .map(|binding| self.binding_value(binding))
+            .fold(0.0, f32::max)
+    }
+
+    /// Serializes the bindings of every action, one action per line, e.g.
+    /// `jump = key:Space, button:a`.
+    ///
+    /// See the [`Display`](enum.Binding.html#impl-Display) implementation of `Binding` for
+    /// the format of each binding.
+    pub fn save_bindings(&self) -> String {
+        let mut s = String::new();
+        for (action, state) in &self.actions {
+            let bindings: Vec<String> = state.bindings.iter().map(|b| b.to_string()).collect();
+            s.push_str(&format!("{} = {}\n", action, bindings.join(", ")));
+        }
+        s
+    }
+
+    /// Loads bindings saved with [`save_bindings`](#method.save_bindings), replacing the
+    /// bindings of the actions it contains. Empty lines and lines starting with `#` are
+    /// ignored.
+    ///
+    /// Nothing is changed if an error is returned.
+    pub fn load_bindings(&mut self, s: &str) -> Result<(), String> {
+        let mut loaded = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let eq = line
+                .find('=')
+                .ok_or_else(|| format!("Missing '=' on line {}", i + 1))?;
+            let action = line[..eq].trim();
+            if action.is_empty() {
+                return Err(format!("Missing action name on line {}", i + 1));
+            }
+            let bindings = split_bindings(&line[eq + 1..])
+                .into_iter()
+                .map(|binding| {
+                    binding
+                        .parse()
+                        .map_err(|e| format!("{} on line {}", e, i + 1))
+                })
+                .collect::<Result<Vec<Binding>, String>>()?;
+            loaded.push((action.to_owned(), bindings));
+        }
+
+        for (action, bindings) in loaded {
+            self.actions.entry(action).or_default().bindings = bindings;
+        }
+        self.update_actions();
+        Ok(())
+    }
+
+    fn binding_value(&self, binding: &Binding) -> f32 {
+        match *binding {
+            Binding::ControllerAxis { axis, threshold } => self
+                .controller_axes
+                .iter()
+                .filter(|&(&(_, a), _)| a == axis)
+                .map(|(_, &value)| {
+                    let value = if threshold < 0.0 { -value } else { value };
+                    if value >= threshold.abs() && value > 0.0 {
+                        value
+                    } else {
+                        0.0
+                    }
+                })
+                .fold(0.0, f32::max),
+            Binding::Key { keycode, keymod } => {
+                held(self.keycodes.contains(&keycode) && mods_match(keymod, self.keymod))
+            }
+            Binding::Scancode { scancode, keymod } => {
+                held(self.scancodes.contains(&scancode) && mods_match(keymod, self.keymod))
+            }
+            Binding::Mouse(button) => held(self.mouse_buttons.contains(&button)),
+            Binding::ControllerButton(button) => {
+                held(self.controller_buttons.iter().any(|&(_, b)| b == button))
+            }
+        }
+    }
+
+    fn update_actions(&mut self) {
+        let mut actions = std::mem::take(&mut self.actions);
+        for state in actions.values_mut() {
+            let pressed = state
+                .bindings
+                .iter()
+                .any(|binding| self.binding_value(binding) > 0.0);
+            if pressed && !state.pressed {
+                state.just_pressed = true;
+            } else if !pressed && state.pressed {
+                state.just_released = true;
+            }
+            state.pressed = pressed;
+        }
+        self.actions = actions;
+    }
+}
+
+impl Default for ActionMap {
+    fn default() -> ActionMap {
+        ActionMap::new()
+    }
+}
+
+fn held(held: bool) -> f32 {
+    if held {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Splits a comma separated list of bindings. Commas that are not followed by the kind of a
+/// binding belong to the previous binding, e.g. in `key[ctrl,shift]:S` or `key:Keypad ,`.
+fn split_bindings(s: &str) -> Vec<&str> {
+    const KINDS: &[&str] = &["key", "scancode", "mouse", "button", "axis"];
+
+    let mut bindings = Vec::new();
+    let mut start = 0;
+    for (i, _) in s.match_indices(',') {
+        let next = s[i + 1..].trim_start();
+        let starts_binding = KINDS.iter().any(|kind| {
+            next.starts_with(kind) && matches!(next[kind.len()..].chars().next(), Some(':' | '['))
+        });
+        if starts_binding {
+            bindings.push(s[start..i].trim());
+            start = i + 1;
+        }
+    }
+    bindings.push(s[start..].trim());
+    bindings.retain(|binding| !binding.is_empty());
+    bindings
+}
+
+/// The inputs held in a snapshot, with the ticks at which they were first seen held.
+struct HeldInputs<T> {
+    held: HashMap<T, u64>,
+    newly_pressed: Vec<T>,
+    /// Inputs released since the previous snapshot, with how long they were held.
+    newly_released: HashMap<T, u64>,
+}
+
+impl<T: Copy + Eq + Hash> HeldInputs<T> {
+    fn new() -> HeldInputs<T> {
+        HeldInputs {
+            held: HashMap::new(),
+            newly_pressed: Vec::new(),
+            newly_released: HashMap::new(),
+        }
+    }
+
+    fn update<I: IntoIterator<Item = T>>(&mut self, pressed: I, ticks: u64) {
+        let pressed: HashSet<T> = pressed.into_iter().collect();
+        self.newly_pressed.clear();
+        self.newly_released.clear();
+
+        let newly_released = &mut self.newly_released;
+        self.held.retain(|input, &mut since| {
+            let held = pressed.contains(input);
+            if !held {
+                newly_released.insert(*input, ticks.saturating_sub(since));
+            }
+            held
+        });
+        for input in pressed {
+            if let Entry::Vacant(entry) = self.held.entry(input) {
+                entry.insert(ticks);
+                self.newly_pressed.push(input);
+            }
+        }
+    }
+
+    fn held_duration(&self, input: &T, ticks: u64) -> Option<u64> {
+        match self.held.get(input) {
+            Some(&since) => Some(ticks.saturating_sub(since)),
+            None => self.newly_released.get(input).copied(),
+        }
+    }
+}
+
+/// Keeps track of the keyboard and mouse buttons across frames, to tell which ones were
+/// pressed or released since the previous frame and for how long they have been held.
+///
+/// Call [`update`](#method.update) once per frame, after the events of the frame have been
+/// pumped, or use [`EventPump::pump_events_into`] to do both at once.
+///
+/// # Example
+/// ```no_run
+/// use sdl2::input::InputSnapshot;
+/// use sdl2::keyboard::Scancode;
+///
+/// let sdl = sdl2::init().unwrap();
+/// let mut event_pump = sdl.event_pump().unwrap();
+///
+/// let mut input = InputSnapshot::new();
+/// loop {
+///     event_pump.pump_events_into(&mut input);
+///
+///     for scancode in input.newly_pressed() {
+///         println!("{} pressed", scancode);
+///     }
+///     if input.held_duration(Scancode::Space).map_or(false, |ms| ms > 1000) {
+///         println!("Space held for more than a second");
+///     }
+/// }
+/// ```
+pub struct InputSnapshot {
+    ticks: u64,
+    scancodes: HeldInputs<Scancode>,
+    mouse_buttons: HeldInputs<MouseButton>,
+}
+
+impl InputSnapshot {
+    /// Creates a snapshot in which nothing is pressed.
+    pub fn new() -> InputSnapshot {
+        InputSnapshot {
+            ticks: 0,
+            scancodes: HeldInputs::new(),
+            mouse_buttons: HeldInputs::new(),
+        }
+    }
+
+    /// Records the current keyboard and mouse state, as of the last time events were pumped.
+    #[doc(alias = "SDL_GetTicks64")]
+    pub fn update(&mut self, event_pump: &EventPump) {
+        let ticks = unsafe { sys::SDL_GetTicks64() };
+        self.update_from(
+            &event_pump.keyboard_state(),
+            &event_pump.mouse_state(),
+            ticks,
+        );
+    }
+
+    /// Records the given keyboard and mouse state, as of `ticks` milliseconds (e.g. from
+    /// [`TimerSubsystem::ticks64`](../struct.TimerSubsystem.html#method.ticks64)).
+    pub fn update_from(&mut self, keyboard: &KeyboardState, mouse: &MouseState, ticks: u64) {
+        self.ticks = ticks;
+        self.scancodes.update(keyboard.pressed_scancodes(), ticks);
+        self.mouse_buttons
+            .update(mouse.pressed_mouse_buttons(), ticks);
+    }
+
+    /// The ticks, in milliseconds, of the last update.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Returns the scancodes that are held.
+    pub fn pressed_scancodes(&self) -> impl Iterator<Item = Scancode> + '_ {
+        self.scancodes.held.keys().copied()
+    }
+
+    pub fn is_scancode_pressed(&self, scancode: Scancode) -> bool {
+        self.scancodes.held.contains_key(&scancode)
+    }
+
+    /// Returns the scancodes that were pressed since the previous update.
+    pub fn newly_pressed(&self) -> impl Iterator<Item = Scancode> + '_ {
+        self.scancodes.newly_pressed.iter().copied()
+    }
+
+    /// Returns the scancodes that were released since the previous update.
+    pub fn newly_released(&self) -> impl Iterator<Item = Scancode> + '_ {
+        self.scancodes.newly_released.keys().copied()
+    }
+
+    /// Returns how long `scancode` has been held, in milliseconds, or how long it was held
+    /// if it was released since the previous update.
+    ///
+    /// Returns `None` if the scancode is not held and was not just released.
+    pub fn held_duration(&self, scancode: Scancode) -> Option<u64> {
+        self.scancodes.held_duration(&scancode, self.ticks)
+    }
+
+    /// Returns the mouse buttons that are held.
+    pub fn pressed_mouse_buttons(&self) -> impl Iterator<Item = MouseButton> + '_ {
+        self.mouse_buttons.held.keys().copied()
+    }
+
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.held.contains_key(&button)
+    }
+
+    /// Returns the mouse buttons that were pressed since the previous update.
+    pub fn newly_pressed_mouse_buttons(&self) -> impl Iterator<Item = MouseButton> + '_ {
+        self.mouse_buttons.newly_pressed.iter().copied()
+    }
+
+    /// Returns the mouse buttons that were released since the previous update.
+    pub fn newly_released_mouse_buttons(&self) -> impl Iterator<Item = MouseButton> + '_ {
+        self.mouse_buttons.newly_released.keys().copied()
+    }
+
+    /// Returns how long `button` has been held, in milliseconds, or how long it was held if
+    /// it was released since the previous update.
+    ///
+    /// Returns `None` if the button is not held and was not just released.
+    pub fn mouse_button_held_duration(&self, button: MouseButton) -> Option<u64> {
+        self.mouse_buttons.held_duration(&button, self.ticks)
+    }
+}
+
+impl Default for InputSnapshot {
+    fn default() -> InputSnapshot {
+        InputSnapshot::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ActionMap, Binding, HeldInputs};
+    use crate::controller::{Axis, Button};
+    use crate::event::Event;
+    use crate::keyboard::{Keycode, Mod, Scancode};
+
+    fn key_event(down: bool, keycode: Keycode, keymod: Mod) -> Event {
+        if down {
+            Event::KeyDown {
+                timestamp: 0,
+                window_id: 0,
+                keycode: Some(keycode),
+                scancode: None,
+                keymod,
+                repeat: false,
+            }
+        } else {
+            Event::KeyUp {
+                timestamp: 0,
+                window_id: 0,
+                keycode: Some(keycode),
+                scancode: None,
+                keymod,
+                repeat: false,
+            }
+        }
+    }
+
+    #[test]
+    fn test_action_state() {
+        let mut actions = ActionMap::new();
+        actions.bind("jump", Binding::key(Keycode::SPACE));
+        actions.bind("jump", Binding::ControllerButton(Button::A));
+
+        actions.begin_frame();
+        actions.handle_event(&key_event(true, Keycode::SPACE, Mod::NOMOD));
+        assert!(actions.pressed("jump"));
+        assert!(actions.just_pressed("jump"));
+        assert_eq!(actions.value("jump"), 1.0);
+
+        // Still held through the controller after releasing the key
+        actions.begin_frame();
+        actions.handle_event(&Event::ControllerButtonDown {
+            timestamp: 0,
+            which: 3,
+            button: Button::A,
+        });
+        actions.handle_event(&key_event(false, Keycode::SPACE, Mod::NOMOD));
+        assert!(actions.pressed("jump"));
+        assert!(!actions.just_pressed("jump"));
+        assert!(!actions.just_released("jump"));
+
+        actions.begin_frame();
+        actions.handle_event(&Event::ControllerDeviceRemoved {
+            timestamp: 0,
+            which: 3,
+        });
+        assert!(!actions.pressed("jump"));
+        assert!(actions.just_released("jump"));
+        assert!(!actions.pressed("unknown action"));
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let mut actions = ActionMap::new();
+        actions.bind(
+            "save",
+            Binding::Key {
+                keycode: Keycode::S,
+                keymod: Mod::LCTRLMOD | Mod::RCTRLMOD,
+            },
+        );
+
+        actions.handle_event(&key_event(true, Keycode::S, Mod::CAPSMOD));
+        assert!(!actions.pressed("save"));
+        actions.handle_event(&key_event(
+            true,
+            Keycode::RCTRL,
+            Mod::RCTRLMOD | Mod::CAPSMOD,
+        ));
+        assert!(actions.pressed("save"));
+        actions.handle_event(&key_event(false, Keycode::RCTRL, Mod::CAPSMOD));
+        assert!(!actions.pressed("save"));
+    }
+
+    #[test]
+    fn test_axis_value() {
+        let mut actions = ActionMap::new();
+        actions.bind(
+            "left",
+            Binding::ControllerAxis {
+                axis: Axis::LeftX,
+                threshold: -0.25,
+            },
+        );
+
+        let motion = |value| Event::ControllerAxisMotion {
+            timestamp: 0,
+            which: 0,
+            axis: Axis::LeftX,
+            value,
+        };
+        actions.handle_event(&motion(-4000));
+        assert!(!actions.pressed("left"));
+        assert_eq!(actions.value("left"), 0.0);
+        actions.handle_event(&motion(i16::MIN));
+        assert!(actions.pressed("left"));
+        assert_eq!(actions.value("left"), 1.0);
+        actions.handle_event(&motion(i16::MAX));
+        assert!(!actions.pressed("left"));
+    }
+
+    #[test]
+    fn test_save_load_bindings() {
+        let mut actions = ActionMap::new();
+        actions.bind("jump", Binding::key(Keycode::SPACE));
+        actions.bind("jump", Binding::ControllerButton(Button::A));
+        actions.bind(
+            "quit",
+            Binding::Key {
+                keycode: Keycode::COMMA,
+                keymod: Mod::LCTRLMOD | Mod::RCTRLMOD | Mod::LSHIFTMOD,
+            },
+        );
+        actions.bind(
+            "quit",
+            Binding::Scancode {
+                scancode: Scancode::F4,
+                keymod: Mod::LALTMOD,
+            },
+        );
+        actions.bind(
+            "left",
+            Binding::ControllerAxis {
+                axis: Axis::LeftX,
+                threshold: -0.5,
+            },
+        );
+        actions.bind("left", Binding::Mouse(crate::mouse::MouseButton::X1));
+        actions.bind("menu", Binding::key(Keycode::COMMA));
+        actions.bind("menu", Binding::key(Keycode::KP_COMMA));
+
+        let saved = actions.save_bindings();
+        let mut loaded = ActionMap::new();
+        loaded.load_bindings(&saved).unwrap();
+        for action in actions.actions() {
+            assert_eq!(actions.bindings(action), loaded.bindings(action));
+        }
+        assert_eq!(saved, loaded.save_bindings());
+
+        assert!(loaded.load_bindings("jump = key:Space, key:Nope").is_err());
+        assert!(loaded.load_bindings("jump").is_err());
+        assert!(loaded.load_bindings("jump = mouse[ctrl]:left").is_err());
+        assert!(loaded.load_bindings("jump = axis:leftx<0.5").is_err());
+        assert_eq!(loaded.bindings("jump"), actions.bindings("jump"));
+    }
+
+    #[test]
+    fn test_held_inputs() {
+        let mut held = HeldInputs::new();
+        held.update(vec![Scancode::A, Scancode::B], 100);
+        let mut pressed = held.newly_pressed.clone();
+        pressed.sort_by_key(|&scancode| scancode as i32);
+        assert_eq!(pressed, [Scancode::A, Scancode::B]);
+        assert_eq!(held.held_duration(&Scancode::A, 100), Some(0));
+
+        held.update(vec![Scancode::B, Scancode::C], 150);
+        assert_eq!(held.newly_pressed, [Scancode::C]);
+        assert_eq!(
+            held.newly_released.keys().collect::<Vec<_>>(),
+            [&Scancode::A]
+        );
+        assert_eq!(held.held_duration(&Scancode::A, 150), Some(50));
+        assert_eq!(held.held_duration(&Scancode::B, 150), Some(50));
+        assert_eq!(held.held_duration(&Scancode::C, 150), Some(0));
+
+        held.update(vec![Scancode::B], 200);
+        assert!(held.newly_pressed.is_empty());
+        assert_eq!(held.held_duration(&Scancode::A, 200), None);
+        assert_eq!(held.held_duration(&Scancode::B, 200), Some(100));
+        assert_eq!(held.held_duration(&Scancode::C, 200), Some(50));
+    }
+}
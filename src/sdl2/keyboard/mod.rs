@@ -197,6 +197,22 @@ impl KeyboardUtil {
     }
 }
 
+/// The modifier set while holding `keycode`.
+pub(crate) fn key_mod(keycode: Keycode) -> Mod {
+    match keycode {
+        Keycode::LCTRL => Mod::LCTRLMOD,
+        Keycode::RCTRL => Mod::RCTRLMOD,
+        Keycode::LSHIFT => Mod::LSHIFTMOD,
+        Keycode::RSHIFT => Mod::RSHIFTMOD,
+        Keycode::LALT => Mod::LALTMOD,
+        Keycode::RALT => Mod::RALTMOD,
+        Keycode::LGUI => Mod::LGUIMOD,
+        Keycode::RGUI => Mod::RGUIMOD,
+        Keycode::MODE => Mod::MODEMOD,
+        _ => Mod::NOMOD,
+    }
+}
+
 /// Text input utility functions. Access with `VideoSubsystem::text_input()`.
 ///
 /// These functions require the video subsystem to be initialized and are not thread-safe.
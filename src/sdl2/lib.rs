@@ -59,6 +59,11 @@ pub extern crate sdl2_sys as sys;
 
 #[cfg(feature = "gfx")]
 extern crate c_vec;
+#[cfg(feature = "futures")]
+extern crate futures_core;
+
+#[cfg(test)]
+extern crate rand;
 
 pub use crate::sdl::*;
 
@@ -72,6 +77,7 @@ pub mod event;
 pub mod filesystem;
 pub mod haptic;
 pub mod hint;
+pub mod input;
 pub mod joystick;
 pub mod keyboard;
 pub mod log;
@@ -85,6 +91,7 @@ mod sdl;
 #[cfg(feature = "hidapi")]
 pub mod sensor;
 pub mod surface;
+pub mod testing;
 pub mod timer;
 pub mod touch;
 pub mod url;
@@ -20,19 +20,24 @@
 //! features = ["mixer"]
 //! ```
 
-use audio::AudioFormatNum;
+use audio::{AllowedChanges, AudioFormatNum};
 use get_error;
 use libc::c_void;
 use libc::{c_double, c_int, c_uint};
 use rwops::RWops;
 use std::borrow::ToOwned;
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::default;
 use std::ffi::{CStr, CString};
 use std::fmt;
 use std::marker::PhantomData;
+use std::mem;
 use std::path::Path;
+use std::ptr;
 use std::str::from_utf8;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use sys;
 use sys::mixer;
 use version::Version;
@@ -103,6 +108,7 @@ bitflags!(
         const OGG  = mixer::MIX_InitFlags_MIX_INIT_OGG;
         const MID  = mixer::MIX_InitFlags_MIX_INIT_MID;
         const OPUS = mixer::MIX_InitFlags_MIX_INIT_OPUS;
+        const WAVPACK = mixer::MIX_InitFlags_MIX_INIT_WAVPACK;
     }
 );
 
@@ -174,12 +180,79 @@ pub fn open_audio(
     }
 }
 
+/// Open the mixer on a specific audio device.
+///
+/// `device` is a playback device name as reported by `AudioSubsystem::playback_devices`,
+/// or `None` for the default device. `allowed_changes` lets the device use a frequency,
+/// format, channel count or buffer size other than the requested one instead of having
+/// SDL convert; use [`query_spec`](fn.query_spec.html) to find out what was obtained.
+/// See [`open_audio`](fn.open_audio.html) for the other parameters.
+///
+/// ```no_run
+/// use sdl2::audio::AllowedChanges;
+/// use sdl2::mixer::{self, DEFAULT_CHANNELS, DEFAULT_FORMAT, DEFAULT_FREQUENCY};
+///
+/// let sdl_context = sdl2::init().unwrap();
+/// let audio_subsystem = sdl_context.audio().unwrap();
+/// let device = audio_subsystem.playback_devices().last().unwrap();
+///
+/// mixer::open_audio_device(
+///     DEFAULT_FREQUENCY,
+///     DEFAULT_FORMAT,
+///     DEFAULT_CHANNELS,
+///     1024,
+///     device.name.as_str(),
+///     AllowedChanges::FREQUENCY,
+/// )
+/// .unwrap();
+/// let (frequency, _format, _channels) = mixer::query_spec().unwrap();
+/// ```
+#[doc(alias = "Mix_OpenAudioDevice")]
+pub fn open_audio_device<'a, D>(
+    frequency: i32,
+    format: AudioFormat,
+    channels: i32,
+    chunksize: i32,
+    device: D,
+    allowed_changes: AllowedChanges,
+) -> Result<(), String>
+where
+    D: Into<Option<&'a str>>,
+{
+    let device = match device.into() {
+        Some(device) => Some(CString::new(device).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    let ret = unsafe {
+        mixer::Mix_OpenAudioDevice(
+            frequency as c_int,
+            format,
+            channels as c_int,
+            chunksize as c_int,
+            device
+                .as_ref()
+                .map_or(ptr::null(), |device| device.as_ptr()),
+            allowed_changes.bits() as c_int,
+        )
+    };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(get_error())
+    }
+}
+
 /// Shutdown and cleanup the mixer API.
 pub fn close_audio() {
     unsafe { mixer::Mix_CloseAudio() }
 }
 
-/// Get the actual audio format in use by the opened audio device.
+/// Get the actual audio format in use by the opened audio device, as
+/// `(frequency, format, channels)`.
+///
+/// These are the values negotiated with the device, which may differ from the requested
+/// ones if the mixer was opened with `open_audio_device` and allowed changes.
+#[doc(alias = "Mix_QuerySpec")]
 pub fn query_spec() -> Result<(i32, AudioFormat, i32), String> {
     let mut frequency: c_int = 0;
     let mut format: u16 = 0;
@@ -239,6 +312,23 @@ impl Chunk {
         Self::from_owned_raw(raw)
     }
 
+    /// Load a sample from an encoded in-memory file, such as a WAVE or OGG file.
+    ///
+    /// The data is decoded right away, so the chunk does not borrow `buf`.
+    pub fn from_bytes(buf: &[u8]) -> Result<Chunk, String> {
+        Chunk::from_rwops(&mut RWops::from_bytes(buf)?)
+    }
+
+    /// Load a sample from an encoded file read from `src`, starting at its current position.
+    ///
+    /// The data is decoded right away and `src` stays open, so further entries of a
+    /// pack file can be read from it afterwards.
+    #[doc(alias = "Mix_LoadWAV_RW")]
+    pub fn from_rwops(src: &mut RWops) -> Result<Chunk, String> {
+        let raw = unsafe { mixer::Mix_LoadWAV_RW(src.raw(), 0) };
+        Self::from_owned_raw(raw)
+    }
+
     /// Load chunk from a buffer containing raw audio data in the mixer format. The length of the
     /// buffer has to fit in 32-bit unsigned integer. The chunk takes ownership of the buffer.
     ///
@@ -323,6 +413,31 @@ pub fn allocate_channels(numchans: i32) -> i32 {
     unsafe { mixer::Mix_AllocateChannels(numchans as c_int) as i32 }
 }
 
+/// Set the master volume for all channels and the music on a scale of 0 to 128,
+/// returning the previous master volume.
+///
+/// This scales on top of the per-channel, per-chunk and music volumes. Values greater
+/// than 128 will use 128, and -1 leaves the volume unchanged. Requires SDL_mixer 2.6.
+///
+/// ```no_run
+/// // Halve the volume of everything that is playing, e.g. while a menu is open.
+/// let previous = sdl2::mixer::set_master_volume(sdl2::mixer::MAX_VOLUME / 2);
+/// // ...
+/// sdl2::mixer::set_master_volume(previous);
+/// ```
+#[cfg(feature = "mixer-2-6")]
+#[doc(alias = "Mix_MasterVolume")]
+pub fn set_master_volume(volume: i32) -> i32 {
+    unsafe { mixer::Mix_MasterVolume(volume as c_int) as i32 }
+}
+
+/// Returns the current master volume. Requires SDL_mixer 2.6.
+#[cfg(feature = "mixer-2-6")]
+#[doc(alias = "Mix_MasterVolume")]
+pub fn get_master_volume() -> i32 {
+    unsafe { mixer::Mix_MasterVolume(-1) as i32 }
+}
+
 static mut CHANNEL_FINISHED_CALLBACK: Option<Box<dyn Fn(Channel) + 'static>> = None;
 
 extern "C" fn c_channel_finished_callback(ch: c_int) {
@@ -503,6 +618,98 @@ impl Channel {
         }
     }
 
+    /// Registers a custom effect that processes the channel's raw audio data, in the
+    /// format reported by `query_spec`, before it is mixed.
+    ///
+    /// Effects run on the audio thread in the order they were registered. `on_done` is
+    /// called once the effect is removed: when the returned `EffectHandle` is dropped,
+    /// or, for regular channels, when the channel finishes playing or is halted (on the
+    /// audio thread in that case). Register effects after starting playback on a channel.
+    /// Use `Channel::post()` to process the final mix instead.
+    #[doc(alias = "Mix_RegisterEffect")]
+    pub fn register_effect<F, D>(self, f: F, on_done: D) -> Result<EffectHandle, String>
+    where
+        F: FnMut(&mut [u8]) + Send + 'static,
+        D: FnOnce() + Send + 'static,
+    {
+        register_effect(self, Box::new(f), Box::new(on_done))
+    }
+
+    /// Registers a custom effect that processes the channel's audio as samples of type `T`.
+    ///
+    /// Fails if `T` does not match the format reported by `query_spec`. See
+    /// [`register_effect`](#method.register_effect).
+    ///
+    /// ```no_run
+    /// use sdl2::mixer::{self, Channel};
+    ///
+    /// # let chunk = mixer::Chunk::from_file("sound.wav").unwrap();
+    /// // A one-pole low-pass filter on a stereo channel.
+    /// let channel = Channel::all().play(&chunk, 0).unwrap();
+    /// let mut state = [0.0f32; 2];
+    /// let _filter = channel
+    ///     .register_typed_effect(
+    ///         move |samples: &mut [i16]| {
+    ///             for frame in samples.chunks_exact_mut(2) {
+    ///                 for (sample, state) in frame.iter_mut().zip(state.iter_mut()) {
+    ///                     *state += (*sample as f32 - *state) * 0.1;
+    ///                     *sample = *state as i16;
+    ///                 }
+    ///             }
+    ///         },
+    ///         || (),
+    ///     )
+    ///     .unwrap();
+    /// ```
+    #[doc(alias = "Mix_RegisterEffect")]
+    pub fn register_typed_effect<T, F, D>(
+        self,
+        mut f: F,
+        on_done: D,
+    ) -> Result<EffectHandle, String>
+    where
+        T: AudioFormatNum + Send + 'static,
+        F: FnMut(&mut [T]) + Send + 'static,
+        D: FnOnce() + Send + 'static,
+    {
+        let (_, format, _) = query_spec()?;
+        if format != T::audio_format() as i32 as AudioFormat {
+            return Err(format!(
+                "Effect sample type does not match the mixer format {:#06x}",
+                format
+            ));
+        }
+        // Reused for buffers that are not aligned for `T`, so that `f` still sees them.
+        let mut scratch: Vec<T> = Vec::new();
+        self.register_effect(
+            move |buf: &mut [u8]| {
+                // Any bit pattern is a valid sample, so reinterpreting the bytes is sound.
+                let (head, samples, _) = unsafe { buf.align_to_mut::<T>() };
+                if head.is_empty() {
+                    f(samples);
+                    return;
+                }
+
+                let len = buf.len() / mem::size_of::<T>();
+                let bytes = len * mem::size_of::<T>();
+                scratch.clear();
+                scratch.extend((0..len).map(|_| T::SILENCE));
+                unsafe {
+                    ptr::copy_nonoverlapping(buf.as_ptr(), scratch.as_mut_ptr() as *mut u8, bytes);
+                }
+                f(&mut scratch);
+                unsafe {
+                    ptr::copy_nonoverlapping(
+                        scratch.as_ptr() as *const u8,
+                        buf.as_mut_ptr(),
+                        bytes,
+                    );
+                }
+            },
+            on_done,
+        )
+    }
+
     /// Sets a panning effect, where left and right is the volume of the left and right channels.
     /// They range from 0 (silence) to 255 (loud).
     pub fn set_panning(self, left: u8, right: u8) -> Result<(), String> {
@@ -714,16 +921,19 @@ pub enum MusicType {
     MusicMp3Mad = mixer::Mix_MusicType_MUS_MP3_MAD_UNUSED as i32,
     MusicFlac = mixer::Mix_MusicType_MUS_FLAC as i32,
     MusicModPlug = mixer::Mix_MusicType_MUS_MODPLUG_UNUSED as i32,
+    MusicOpus = mixer::Mix_MusicType_MUS_OPUS as i32,
+    MusicWavPack = mixer::Mix_MusicType_MUS_WAVPACK as i32,
+    MusicGme = mixer::Mix_MusicType_MUS_GME as i32,
 }
 
 // hooks
-static mut MUSIC_FINISHED_HOOK: Option<fn()> = None;
+static mut MUSIC_FINISHED_HOOK: Option<Box<dyn Fn() + Send + 'static>> = None;
 
 extern "C" fn c_music_finished_hook() {
     unsafe {
         match MUSIC_FINISHED_HOOK {
             None => (),
-            Some(f) => f(),
+            Some(ref f) => f(),
         }
     }
 }
@@ -791,6 +1001,42 @@ impl<'a> Music<'a> {
         }
     }
 
+    /// Load music from `src`, which is read from as the music plays and therefore kept
+    /// open until the `Music` is dropped.
+    ///
+    /// `hint` names the format of the data; pass `MusicType::MusicNone` to detect it
+    /// from the data instead, which fails for formats without a recognizable header.
+    ///
+    /// ```no_run
+    /// use sdl2::mixer::{Music, MusicType};
+    /// use sdl2::rwops::RWops;
+    ///
+    /// let pack: Vec<u8> = std::fs::read("assets.pack").unwrap();
+    /// // The range of the track within the pack file, from its table of contents.
+    /// let track = &pack[1024..65536];
+    /// let music = Music::from_rwops(RWops::from_bytes(track).unwrap(), MusicType::MusicOgg).unwrap();
+    /// music.play(-1).unwrap();
+    /// ```
+    #[doc(alias = "Mix_LoadMUSType_RW")]
+    pub fn from_rwops(src: RWops<'a>, hint: MusicType) -> Result<Music<'a>, String> {
+        // SDL_mixer takes ownership of the stream and closes it when the music is freed,
+        // or right away if loading fails. The borrow of its data lives on in `Music<'a>`.
+        let raw = unsafe {
+            let rw = src.raw();
+            mem::forget(src);
+            mixer::Mix_LoadMUSType_RW(rw, hint as i32 as mixer::Mix_MusicType, 1)
+        };
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            Ok(Music {
+                raw,
+                owned: true,
+                _marker: PhantomData,
+            })
+        }
+    }
+
     /// The file format encoding of the music.
     pub fn get_type(&self) -> MusicType {
         let ret = unsafe { mixer::Mix_GetMusicType(self.raw) as i32 } as c_uint;
@@ -804,10 +1050,154 @@ impl<'a> Music<'a> {
             mixer::Mix_MusicType_MUS_MP3_MAD_UNUSED => MusicType::MusicMp3Mad,
             mixer::Mix_MusicType_MUS_FLAC => MusicType::MusicFlac,
             mixer::Mix_MusicType_MUS_MODPLUG_UNUSED => MusicType::MusicModPlug,
+            mixer::Mix_MusicType_MUS_OPUS => MusicType::MusicOpus,
+            mixer::Mix_MusicType_MUS_WAVPACK => MusicType::MusicWavPack,
+            mixer::Mix_MusicType_MUS_GME => MusicType::MusicGme,
             mixer::Mix_MusicType_MUS_NONE | _ => MusicType::MusicNone,
         }
     }
 
+    /// The title of the music, falling back to its file name when it has no title tag.
+    /// Requires SDL_mixer 2.6.
+    ///
+    /// ```no_run
+    /// let music = sdl2::mixer::Music::from_file("song.ogg").unwrap();
+    /// let title = music.title().unwrap_or_default();
+    /// match music.artist() {
+    ///     Some(artist) => println!("Now playing {} by {}", title, artist),
+    ///     None => println!("Now playing {}", title),
+    /// }
+    /// ```
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicTitle")]
+    pub fn title(&self) -> Option<String> {
+        unsafe { music_tag(mixer::Mix_GetMusicTitle(self.raw)) }
+    }
+
+    /// The title tag of the music, without falling back to its file name.
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicTitleTag")]
+    pub fn title_tag(&self) -> Option<String> {
+        unsafe { music_tag(mixer::Mix_GetMusicTitleTag(self.raw)) }
+    }
+
+    /// The artist tag of the music.
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicArtistTag")]
+    pub fn artist(&self) -> Option<String> {
+        unsafe { music_tag(mixer::Mix_GetMusicArtistTag(self.raw)) }
+    }
+
+    /// The album tag of the music.
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicAlbumTag")]
+    pub fn album(&self) -> Option<String> {
+        unsafe { music_tag(mixer::Mix_GetMusicAlbumTag(self.raw)) }
+    }
+
+    /// The copyright tag of the music.
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicCopyrightTag")]
+    pub fn copyright(&self) -> Option<String> {
+        unsafe { music_tag(mixer::Mix_GetMusicCopyrightTag(self.raw)) }
+    }
+
+    /// The total length of the music in seconds. Requires SDL_mixer 2.6.
+    ///
+    /// ```no_run
+    /// let music = sdl2::mixer::Music::from_file("song.ogg").unwrap();
+    /// music.play(1).unwrap();
+    /// let duration = music.duration().unwrap();
+    /// let position = music.position().unwrap();
+    /// println!("{:.0}s / {:.0}s", position, duration);
+    /// ```
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_MusicDuration")]
+    pub fn duration(&self) -> Result<f64, String> {
+        let ret = unsafe { mixer::Mix_MusicDuration(self.raw) };
+        if ret < 0.0 {
+            Err(get_error())
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// The current playback position of the music in seconds, counted from the start
+    /// of the music even when it is not the one playing. Requires SDL_mixer 2.6.
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicPosition")]
+    pub fn position(&self) -> Result<f64, String> {
+        let ret = unsafe { mixer::Mix_GetMusicPosition(self.raw) };
+        if ret < 0.0 {
+            Err(get_error())
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// The loop start point in seconds, from the `LOOPSTART` tag of OGG, FLAC, MP3 and
+    /// Opus files. Returns `None` when the music has no loop points.
+    ///
+    /// ```no_run
+    /// let music = sdl2::mixer::Music::from_file("level.ogg").unwrap();
+    /// if let (Some(start), Some(end)) = (music.loop_start(), music.loop_end()) {
+    ///     println!("The music loops from {:.2}s to {:.2}s", start, end);
+    /// }
+    /// ```
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicLoopStartTime")]
+    pub fn loop_start(&self) -> Option<f64> {
+        music_time(unsafe { mixer::Mix_GetMusicLoopStartTime(self.raw) })
+    }
+
+    /// The loop end point in seconds, or `None` when the music has no loop points.
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicLoopEndTime")]
+    pub fn loop_end(&self) -> Option<f64> {
+        music_time(unsafe { mixer::Mix_GetMusicLoopEndTime(self.raw) })
+    }
+
+    /// The length of the looped section in seconds, or `None` when the music has no
+    /// loop points.
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetMusicLoopLengthTime")]
+    pub fn loop_length(&self) -> Option<f64> {
+        music_time(unsafe { mixer::Mix_GetMusicLoopLengthTime(self.raw) })
+    }
+
+    /// The number of tracks in a multi-track file, such as the game music formats
+    /// played through libgme. Requires SDL_mixer 2.6.
+    ///
+    /// ```no_run
+    /// let music = sdl2::mixer::Music::from_file("soundtrack.nsf").unwrap();
+    /// let tracks = music.get_tracks_number().unwrap();
+    /// music.play(-1).unwrap();
+    /// // Start with the last track of the file
+    /// music.start_track(tracks - 1).unwrap();
+    /// ```
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_GetNumTracks")]
+    pub fn get_tracks_number(&self) -> Result<i32, String> {
+        let ret = unsafe { mixer::Mix_GetNumTracks(self.raw) };
+        if ret == -1 {
+            Err(get_error())
+        } else {
+            Ok(ret as i32)
+        }
+    }
+
+    /// Switch a multi-track music to the indexed track, counting from 0.
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_StartTrack")]
+    pub fn start_track(&self, track: i32) -> Result<(), String> {
+        let ret = unsafe { mixer::Mix_StartTrack(self.raw, track as c_int) };
+        if ret == -1 {
+            Err(get_error())
+        } else {
+            Ok(())
+        }
+    }
+
     /// Play the loaded music loop times through from start to finish. Pass -1 to loop forever.
     pub fn play(&self, loops: i32) -> Result<(), String> {
         let ret = unsafe { mixer::Mix_PlayMusic(self.raw, loops as c_int) };
@@ -885,6 +1275,25 @@ impl<'a> Music<'a> {
         }
     }
 
+    /// Jump to the pattern order of the currently playing MOD music. Requires SDL_mixer 2.6.
+    ///
+    /// ```no_run
+    /// let music = sdl2::mixer::Music::from_file("boss.xm").unwrap();
+    /// music.play(-1).unwrap();
+    /// // Skip the intro, which ends at the 4th pattern order.
+    /// sdl2::mixer::Music::jump_to_order(4).unwrap();
+    /// ```
+    #[cfg(feature = "mixer-2-6")]
+    #[doc(alias = "Mix_ModMusicJumpToOrder")]
+    pub fn jump_to_order(order: i32) -> Result<(), String> {
+        let ret = unsafe { mixer::Mix_ModMusicJumpToOrder(order as c_int) };
+        if ret == -1 {
+            Err(get_error())
+        } else {
+            Ok(())
+        }
+    }
+
     /// Setup a command line music player to use to play music.
     pub fn set_command(command: &str) -> Result<(), String> {
         let ret = unsafe {
@@ -915,9 +1324,6 @@ impl<'a> Music<'a> {
         }
     }
 
-    // TODO: Mix_HookMusic
-    // TODO: Mix_GetMusicHookData
-
     /// Sets up a function to be called when music playback is halted.
     ///
     /// # Examples
@@ -929,9 +1335,24 @@ impl<'a> Music<'a> {
     ///
     /// sdl2::mixer::Music::hook_finished(after_music);
     /// ```
-    pub fn hook_finished(f: fn()) {
+    ///
+    /// Closures work as well; they are called from the audio thread:
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let finished = Arc::new(AtomicBool::new(false));
+    /// let flag = finished.clone();
+    /// sdl2::mixer::Music::hook_finished(move || flag.store(true, Ordering::SeqCst));
+    /// ```
+    pub fn hook_finished(f: impl Fn() + Send + 'static) {
         unsafe {
-            MUSIC_FINISHED_HOOK = Some(f);
+            // Unhook first: SDL_mixer calls the hook with the audio device locked, and
+            // `Mix_HookMusicFinished` takes that lock, so the previous function is no longer
+            // running once it returns and can be dropped.
+            mixer::Mix_HookMusicFinished(None);
+            MUSIC_FINISHED_HOOK = Some(Box::new(f));
             mixer::Mix_HookMusicFinished(Some(c_music_finished_hook as extern "C" fn()));
         }
     }
@@ -966,8 +1387,295 @@ impl<'a> Music<'a> {
     }
 }
 
+/// SDL_mixer returns an empty string for missing tags.
+#[cfg(feature = "mixer-2-6")]
+unsafe fn music_tag(tag: *const libc::c_char) -> Option<String> {
+    if tag.is_null() {
+        return None;
+    }
+    let tag = CStr::from_ptr(tag).to_string_lossy();
+    if tag.is_empty() {
+        None
+    } else {
+        Some(tag.into_owned())
+    }
+}
+
+/// SDL_mixer returns -1.0 for missing loop points.
+#[cfg(feature = "mixer-2-6")]
+fn music_time(time: f64) -> Option<f64> {
+    if time < 0.0 {
+        None
+    } else {
+        Some(time)
+    }
+}
+
 // 4.6 Effects
 
-// TODO: Mix_RegisterEffect
-// TODO: Mix_UnregisterEffect
-// TODO: Mix_SetPostMix
+type EffectFn = Box<dyn FnMut(&mut [u8]) + Send>;
+type EffectDoneFn = Box<dyn FnOnce() + Send>;
+
+struct EffectChainState {
+    /// Cleared once SDL_mixer has dropped the chain; no effects may be added after that.
+    registered: bool,
+    effects: Vec<(u64, EffectFn, EffectDoneFn)>,
+}
+
+/// All Rust effects of a channel, run by a single `Mix_RegisterEffect` registration.
+///
+/// `Mix_UnregisterEffect` removes effects by function pointer, so registering every
+/// closure through the same trampoline would make it impossible to remove a specific one.
+struct EffectChain {
+    channel: i32,
+    state: Mutex<EffectChainState>,
+}
+
+lazy_static! {
+    static ref EFFECT_CHAINS: Mutex<HashMap<i32, Arc<EffectChain>>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_EFFECT_ID: AtomicU64 = AtomicU64::new(0);
+
+unsafe extern "C" fn c_effect_chain(
+    _chan: c_int,
+    stream: *mut c_void,
+    len: c_int,
+    udata: *mut c_void,
+) {
+    let chain = &*(udata as *const EffectChain);
+    let buf = ::std::slice::from_raw_parts_mut(stream as *mut u8, len as usize);
+    if let Ok(mut state) = chain.state.lock() {
+        for (_, effect, _) in state.effects.iter_mut() {
+            effect(buf);
+        }
+    }
+}
+
+unsafe extern "C" fn c_effect_chain_done(_chan: c_int, udata: *mut c_void) {
+    // Takes back the reference handed to SDL_mixer in `register_effect`.
+    let chain = Arc::from_raw(udata as *const EffectChain);
+    let effects = match chain.state.lock() {
+        Ok(mut state) => {
+            state.registered = false;
+            mem::take(&mut state.effects)
+        }
+        Err(_) => return,
+    };
+    for (_, _, on_done) in effects {
+        on_done();
+    }
+}
+
+fn register_effect(
+    Channel(ch): Channel,
+    effect: EffectFn,
+    on_done: EffectDoneFn,
+) -> Result<EffectHandle, String> {
+    let id = NEXT_EFFECT_ID.fetch_add(1, Ordering::Relaxed);
+    let mut chains = EFFECT_CHAINS.lock().unwrap();
+
+    if let Some(chain) = chains.get(&ch) {
+        let mut state = chain.state.lock().unwrap();
+        if state.registered {
+            state.effects.push((id, effect, on_done));
+            return Ok(EffectHandle {
+                chain: chain.clone(),
+                id,
+            });
+        }
+    }
+
+    let chain = Arc::new(EffectChain {
+        channel: ch,
+        state: Mutex::new(EffectChainState {
+            registered: true,
+            effects: vec![(id, effect, on_done)],
+        }),
+    });
+    let udata = Arc::into_raw(chain.clone());
+    let ret = unsafe {
+        mixer::Mix_RegisterEffect(
+            ch as c_int,
+            Some(c_effect_chain),
+            Some(c_effect_chain_done),
+            udata as *mut c_void,
+        )
+    };
+    if ret == 0 {
+        unsafe { drop(Arc::from_raw(udata)) };
+        return Err(get_error());
+    }
+    chains.insert(ch, chain.clone());
+    Ok(EffectHandle { chain, id })
+}
+
+/// A custom effect registered with `Channel::register_effect`. The effect is removed
+/// when this is dropped.
+///
+/// Dropping the handle after SDL_mixer already removed the effect (for example because
+/// the channel finished playing) does nothing.
+pub struct EffectHandle {
+    chain: Arc<EffectChain>,
+    id: u64,
+}
+
+impl EffectHandle {
+    /// The channel the effect was registered on.
+    pub fn channel(&self) -> Channel {
+        Channel(self.chain.channel)
+    }
+
+    /// Whether the effect is still registered.
+    pub fn is_registered(&self) -> bool {
+        let state = self.chain.state.lock().unwrap();
+        state.registered && state.effects.iter().any(|&(id, _, _)| id == self.id)
+    }
+}
+
+impl Drop for EffectHandle {
+    #[doc(alias = "Mix_UnregisterEffect")]
+    fn drop(&mut self) {
+        let (removed, unregister) = {
+            let mut state = match self.chain.state.lock() {
+                Ok(state) => state,
+                Err(_) => return,
+            };
+            let removed = state
+                .effects
+                .iter()
+                .position(|&(id, _, _)| id == self.id)
+                .map(|index| state.effects.remove(index));
+            let unregister = state.registered && state.effects.is_empty();
+            if unregister {
+                // Keeps `register_effect` from adding to a chain that is going away.
+                state.registered = false;
+            }
+            (removed, unregister)
+        };
+
+        if unregister {
+            // The audio thread holds the audio lock while taking the chain lock, so the chain
+            // must not be locked here. Older chains of this channel come first in SDL_mixer's
+            // list, so this removes this chain even if a new one was registered meanwhile.
+            unsafe {
+                mixer::Mix_UnregisterEffect(self.chain.channel as c_int, Some(c_effect_chain));
+            }
+        }
+        if let Some((_, _, on_done)) = removed {
+            on_done();
+        }
+    }
+}
+
+type AudioHookFn = Box<dyn FnMut(&mut [u8]) + Send>;
+
+unsafe extern "C" fn c_audio_hook(udata: *mut c_void, stream: *mut u8, len: c_int) {
+    let f = &mut *(udata as *mut AudioHookFn);
+    f(::std::slice::from_raw_parts_mut(stream, len as usize));
+}
+
+lazy_static! {
+    // The hook data currently installed in SDL_mixer, so that dropping a replaced hook's
+    // guard does not remove its successor.
+    static ref POST_MIX_HOOK: Mutex<usize> = Mutex::new(0);
+    static ref MUSIC_HOOK: Mutex<usize> = Mutex::new(0);
+}
+
+type MixHookSetter =
+    unsafe extern "C" fn(Option<unsafe extern "C" fn(*mut c_void, *mut u8, c_int)>, *mut c_void);
+
+/// Owns a hook installed with `set_post_mix` or `hook_music`.
+struct AudioHook {
+    data: *mut AudioHookFn,
+    current: &'static Mutex<usize>,
+    set: MixHookSetter,
+}
+
+// The hook data is only touched by the audio thread while installed, and by `drop` after that.
+unsafe impl Send for AudioHook {}
+
+impl AudioHook {
+    fn install(current: &'static Mutex<usize>, set: MixHookSetter, f: AudioHookFn) -> AudioHook {
+        let data = Box::into_raw(Box::new(f));
+        let mut current_data = current.lock().unwrap();
+        // SDL_mixer locks the audio device while swapping hooks, so the previous hook is
+        // not running anymore once this returns.
+        unsafe { set(Some(c_audio_hook), data as *mut c_void) };
+        *current_data = data as usize;
+        AudioHook { data, current, set }
+    }
+}
+
+impl Drop for AudioHook {
+    fn drop(&mut self) {
+        let mut current_data = self.current.lock().unwrap();
+        if *current_data == self.data as usize {
+            unsafe { (self.set)(None, ptr::null_mut()) };
+            *current_data = 0;
+        }
+        unsafe { drop(Box::from_raw(self.data)) };
+    }
+}
+
+/// A post-mix hook installed with [`set_post_mix`](fn.set_post_mix.html).
+/// The hook is removed when this is dropped.
+pub struct PostMixHook {
+    _hook: AudioHook,
+}
+
+/// Installs a function that sees, and may modify, the final mix of all channels and
+/// the music right before it is played, for analyzers and VU meters.
+///
+/// The function is called on the audio thread with the raw audio data, in the format
+/// reported by `query_spec`. Only one post-mix hook can be installed; installing
+/// another one replaces it.
+///
+/// ```no_run
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use std::sync::Arc;
+///
+/// // A peak meter for signed 16-bit output.
+/// let peak = Arc::new(AtomicU32::new(0));
+/// let meter = peak.clone();
+/// let _hook = sdl2::mixer::set_post_mix(move |stream| {
+///     let loudest = stream
+///         .chunks_exact(2)
+///         .map(|s| i16::from_ne_bytes([s[0], s[1]]).unsigned_abs() as u32)
+///         .max()
+///         .unwrap_or(0);
+///     meter.store(loudest, Ordering::Relaxed);
+/// });
+/// ```
+#[doc(alias = "Mix_SetPostMix")]
+pub fn set_post_mix<F>(f: F) -> PostMixHook
+where
+    F: FnMut(&mut [u8]) + Send + 'static,
+{
+    PostMixHook {
+        _hook: AudioHook::install(&POST_MIX_HOOK, mixer::Mix_SetPostMix, Box::new(f)),
+    }
+}
+
+/// A music hook installed with [`hook_music`](fn.hook_music.html).
+/// The hook is removed when this is dropped.
+pub struct MusicHook {
+    _hook: AudioHook,
+}
+
+/// Replaces music playback with a function that generates the music data, for
+/// procedural music.
+///
+/// The function is called on the audio thread to fill a buffer of raw audio data, in
+/// the format reported by `query_spec`, which is then mixed with the channels. Any
+/// playing `Music` is stopped while the hook is installed. Only one music hook can be
+/// installed; installing another one replaces it.
+#[doc(alias = "Mix_HookMusic")]
+pub fn hook_music<F>(f: F) -> MusicHook
+where
+    F: FnMut(&mut [u8]) + Send + 'static,
+{
+    MusicHook {
+        _hook: AudioHook::install(&MUSIC_HOOK, mixer::Mix_HookMusic, Box::new(f)),
+    }
+}
@@ -0,0 +1,448 @@
+//! Helpers for testing code that handles input events without a user at the keyboard.
+
+use std::collections::HashMap;
+
+use crate::event::Event;
+use crate::keyboard::{key_mod, KeyboardUtil, Keycode, Mod, Scancode};
+use crate::mouse::{MouseButton, MouseState, MouseWheelDirection};
+use crate::sys;
+use crate::video::Window;
+use crate::EventSubsystem;
+
+/// Synthesizes realistic sequences of input events and pushes them to the event queue with
+/// [`EventSubsystem::push_event`].
+///
+/// The injector keeps track of the modifiers, the mouse position and buttons, and the touching
+/// fingers, so that the events it pushes are consistent with each other: e.g. a key press
+/// while holding shift carries `LSHIFTMOD`, and a mouse motion while dragging carries the held
+/// button in its `mousestate`.
+///
+/// SDL's own input state is kept consistent where SDL allows it: the modifier state returned by
+/// [`KeyboardUtil::mod_state`](../keyboard/struct.KeyboardUtil.html#method.mod_state) is
+/// updated, and with [`set_warp_mouse`](#method.set_warp_mouse) the mouse position returned by
+/// [`MouseState`] follows the injected motions. Pressed keys and mouse buttons cannot be set,
+/// so [`KeyboardState`](../keyboard/struct.KeyboardState.html) and the buttons of
+/// [`MouseState`] only reflect real input.
+///
+/// # Example
+/// ```no_run
+/// use sdl2::event::Event;
+/// use sdl2::keyboard::Keycode;
+/// use sdl2::mouse::MouseButton;
+/// use sdl2::testing::InputInjector;
+///
+/// let sdl = sdl2::init().unwrap();
+/// let ev = sdl.event().unwrap();
+/// let mut event_pump = sdl.event_pump().unwrap();
+///
+/// let mut input = InputInjector::new(&ev);
+/// input.type_text("Hi!").unwrap();
+/// input.key_press(Keycode::RETURN).unwrap();
+/// input.mouse_move(10, 10).unwrap();
+/// input.drag(MouseButton::Left, 100, 50, 4).unwrap();
+///
+/// let text: String = event_pump
+///     .poll_iter()
+///     .filter_map(|event| match event {
+///         Event::TextInput { text, .. } => Some(text),
+///         _ => None,
+///     })
+///     .collect();
+/// assert_eq!(text, "Hi!");
+/// ```
+pub struct InputInjector {
+    event: EventSubsystem,
+    keyboard: KeyboardUtil,
+    window: Option<Window>,
+    window_id: u32,
+    warp_mouse: bool,
+    keymod: Mod,
+    mouse_x: i32,
+    mouse_y: i32,
+    mouse_buttons: u32,
+    touch_id: i64,
+    /// The position of the touching fingers.
+    fingers: HashMap<i64, (f32, f32)>,
+}
+
+impl InputInjector {
+    /// Creates an injector whose events are not associated with a window.
+    pub fn new(event: &EventSubsystem) -> InputInjector {
+        InputInjector {
+            event: event.clone(),
+            keyboard: event.sdl().keyboard(),
+            window: None,
+            window_id: 0,
+            warp_mouse: false,
+            keymod: Mod::NOMOD,
+            mouse_x: 0,
+            mouse_y: 0,
+            mouse_buttons: 0,
+            touch_id: 1,
+            fingers: HashMap::new(),
+        }
+    }
+
+    /// Creates an injector whose events are sent to `window`, as if it had the input focus.
+    pub fn for_window(event: &EventSubsystem, window: &Window) -> InputInjector {
+        InputInjector {
+            window: Some(window.clone()),
+            window_id: window.id(),
+            ..InputInjector::new(event)
+        }
+    }
+
+    /// Sets whether mouse motions warp the mouse cursor of the window with
+    /// `SDL_WarpMouseInWindow`, so that the mouse position known by SDL follows them.
+    ///
+    /// When enabled, the motion events are generated by SDL rather than pushed by the
+    /// injector, so their `mousestate` only reflects real mouse buttons. This has no effect
+    /// for injectors created without a window.
+    pub fn set_warp_mouse(&mut self, warp_mouse: bool) {
+        self.warp_mouse = warp_mouse;
+    }
+
+    /// Sets the touch device of the finger events. Defaults to 1.
+    pub fn set_touch_id(&mut self, touch_id: i64) {
+        self.touch_id = touch_id;
+    }
+
+    /// The modifiers currently held.
+    pub fn keymod(&self) -> Mod {
+        self.keymod
+    }
+
+    /// The current mouse position.
+    pub fn mouse_position(&self) -> (i32, i32) {
+        (self.mouse_x, self.mouse_y)
+    }
+
+    /// The mouse buttons currently held.
+    pub fn mouse_state(&self) -> MouseState {
+        MouseState::from_sdl_state(self.mouse_buttons)
+    }
+
+    fn timestamp() -> u32 {
+        unsafe { sys::SDL_GetTicks() }
+    }
+
+    fn push(&self, event: Event) -> Result<(), String> {
+        self.event.push_event(event)
+    }
+
+    // ------------------------------------------------------------------------------------------
+    // Keyboard
+
+    /// Pushes a key down event. Pressing a modifier key adds its modifier to the following
+    /// events.
+    #[doc(alias = "SDL_SetModState")]
+    pub fn key_down(&mut self, keycode: Keycode) -> Result<(), String> {
+        self.set_keymod(self.keymod | key_mod(keycode));
+        self.push(Event::KeyDown {
+            timestamp: Self::timestamp(),
+            window_id: self.window_id,
+            keycode: Some(keycode),
+            scancode: Scancode::from_keycode(keycode),
+            keymod: self.keymod,
+            repeat: false,
+        })
+    }
+
+    /// Pushes a key up event. Releasing a modifier key removes its modifier from the following
+    /// events.
+    pub fn key_up(&mut self, keycode: Keycode) -> Result<(), String> {
+        self.set_keymod(self.keymod & !key_mod(keycode));
+        self.push(Event::KeyUp {
+            timestamp: Self::timestamp(),
+            window_id: self.window_id,
+            keycode: Some(keycode),
+            scancode: Scancode::from_keycode(keycode),
+            keymod: self.keymod,
+            repeat: false,
+        })
+    }
+
+    /// Pushes a key down event followed by a key up event.
+    pub fn key_press(&mut self, keycode: Keycode) -> Result<(), String> {
+        self.key_down(keycode)?;
+        self.key_up(keycode)
+    }
+
+    /// Presses `keycode` while holding the `modifiers` keys, e.g.
+    /// `key_combo(&[Keycode::LCTRL], Keycode::S)`.
+    pub fn key_combo(&mut self, modifiers: &[Keycode], keycode: Keycode) -> Result<(), String> {
+        for &modifier in modifiers {
+            self.key_down(modifier)?;
+        }
+        self.key_press(keycode)?;
+        for &modifier in modifiers.iter().rev() {
+            self.key_up(modifier)?;
+        }
+        Ok(())
+    }
+
+    /// Types `text`, pushing the key events and the text input events a keyboard with a US
+    /// layout would generate.
+    ///
+    /// Uppercase letters and the other shifted characters of the US layout, such as `!` or
+    /// `{`, are typed while holding left shift. Characters without a key on that layout only
+    /// generate text input events.
+    pub fn type_text(&mut self, text: &str) -> Result<(), String> {
+        for c in text.chars() {
+            let (keycode, shifted) = match us_key(c) {
+                Some((keycode, shifted)) => (Some(keycode), shifted),
+                None => (None, false),
+            };
+            let shift = shifted && !self.keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+
+            if shift {
+                self.key_down(Keycode::LSHIFT)?;
+            }
+            if let Some(keycode) = keycode {
+                self.key_down(keycode)?;
+            }
+            if !c.is_control() {
+                self.push(Event::TextInput {
+                    timestamp: Self::timestamp(),
+                    window_id: self.window_id,
+                    text: c.to_string(),
+                })?;
+            }
+            if let Some(keycode) = keycode {
+                self.key_up(keycode)?;
+            }
+            if shift {
+                self.key_up(Keycode::LSHIFT)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_keymod(&mut self, keymod: Mod) {
+        self.keymod = keymod;
+        self.keyboard.set_mod_state(keymod);
+    }
+
+    // ------------------------------------------------------------------------------------------
+    // Mouse
+
+    /// Moves the mouse to `(x, y)`, pushing a mouse motion event.
+    #[doc(alias = "SDL_WarpMouseInWindow")]
+    pub fn mouse_move(&mut self, x: i32, y: i32) -> Result<(), String> {
+        let (xrel, yrel) = (x - self.mouse_x, y - self.mouse_y);
+        self.mouse_x = x;
+        self.mouse_y = y;
+
+        match self.window {
+            Some(ref window) if self.warp_mouse => {
+                unsafe { sys::SDL_WarpMouseInWindow(window.raw(), x, y) };
+                Ok(())
+            }
+            _ => self.push(Event::MouseMotion {
+                timestamp: Self::timestamp(),
+                window_id: self.window_id,
+                which: 0,
+                mousestate: self.mouse_state(),
+                x,
+                y,
+                xrel,
+                yrel,
+            }),
+        }
+    }
+
+    /// Pushes a mouse button down event at the current mouse position.
+    pub fn mouse_down(&mut self, button: MouseButton) -> Result<(), String> {
+        self.mouse_down_clicks(button, 1)
+    }
+
+    /// Pushes a mouse button up event at the current mouse position.
+    pub fn mouse_up(&mut self, button: MouseButton) -> Result<(), String> {
+        self.mouse_up_clicks(button, 1)
+    }
+
+    /// Pushes a mouse button down event followed by a mouse button up event.
+    pub fn click(&mut self, button: MouseButton) -> Result<(), String> {
+        self.mouse_down_clicks(button, 1)?;
+        self.mouse_up_clicks(button, 1)
+    }
+
+    /// Clicks twice, the second click being reported as a double click.
+    pub fn double_click(&mut self, button: MouseButton) -> Result<(), String> {
+        self.click(button)?;
+        self.mouse_down_clicks(button, 2)?;
+        self.mouse_up_clicks(button, 2)
+    }
+
+    /// Drags the mouse from its current position to `(x, y)` while holding `button`, moving
+    /// in `steps` motion events.
+    pub fn drag(&mut self, button: MouseButton, x: i32, y: i32, steps: u32) -> Result<(), String> {
+        let (start_x, start_y) = (self.mouse_x, self.mouse_y);
+        let steps = steps.max(1) as i32;
+
+        self.mouse_down(button)?;
+        for step in 1..=steps {
+            self.mouse_move(
+                start_x + (x - start_x) * step / steps,
+                start_y + (y - start_y) * step / steps,
+            )?;
+        }
+        self.mouse_up(button)
+    }
+
+    /// Pushes a mouse wheel event, positive `y` scrolling away from the user.
+    pub fn wheel(&mut self, x: i32, y: i32) -> Result<(), String> {
+        self.push(Event::MouseWheel {
+            timestamp: Self::timestamp(),
+            window_id: self.window_id,
+            which: 0,
+            x,
+            y,
+            direction: MouseWheelDirection::Normal,
+            precise_x: x as f32,
+            precise_y: y as f32,
+            mouse_x: self.mouse_x,
+            mouse_y: self.mouse_y,
+        })
+    }
+
+    fn mouse_down_clicks(&mut self, button: MouseButton, clicks: u8) -> Result<(), String> {
+        self.mouse_buttons |= button_mask(button);
+        self.push(Event::MouseButtonDown {
+            timestamp: Self::timestamp(),
+            window_id: self.window_id,
+            which: 0,
+            mouse_btn: button,
+            clicks,
+            x: self.mouse_x,
+            y: self.mouse_y,
+        })
+    }
+
+    fn mouse_up_clicks(&mut self, button: MouseButton, clicks: u8) -> Result<(), String> {
+        self.mouse_buttons &= !button_mask(button);
+        self.push(Event::MouseButtonUp {
+            timestamp: Self::timestamp(),
+            window_id: self.window_id,
+            which: 0,
+            mouse_btn: button,
+            clicks,
+            x: self.mouse_x,
+            y: self.mouse_y,
+        })
+    }
+
+    // ------------------------------------------------------------------------------------------
+    // Touch
+
+    /// Puts finger `finger_id` down at the normalized position `(x, y)`.
+    pub fn finger_down(&mut self, finger_id: i64, x: f32, y: f32) -> Result<(), String> {
+        self.fingers.insert(finger_id, (x, y));
+        self.push(Event::FingerDown {
+            timestamp: Self::timestamp(),
+            touch_id: self.touch_id,
+            finger_id,
+            x,
+            y,
+            dx: 0.0,
+            dy: 0.0,
+            pressure: 1.0,
+        })
+    }
+
+    /// Moves finger `finger_id`, which must be down, to the normalized position `(x, y)`.
+    pub fn finger_move(&mut self, finger_id: i64, x: f32, y: f32) -> Result<(), String> {
+        let (old_x, old_y) = match self.fingers.get_mut(&finger_id) {
+            Some(position) => std::mem::replace(position, (x, y)),
+            None => return Err(format!("Finger {} is not down", finger_id)),
+        };
+        self.push(Event::FingerMotion {
+            timestamp: Self::timestamp(),
+            touch_id: self.touch_id,
+            finger_id,
+            x,
+            y,
+            dx: x - old_x,
+            dy: y - old_y,
+            pressure: 1.0,
+        })
+    }
+
+    /// Lifts finger `finger_id`, which must be down.
+    pub fn finger_up(&mut self, finger_id: i64) -> Result<(), String> {
+        let (x, y) = self
+            .fingers
+            .remove(&finger_id)
+            .ok_or_else(|| format!("Finger {} is not down", finger_id))?;
+        self.push(Event::FingerUp {
+            timestamp: Self::timestamp(),
+            touch_id: self.touch_id,
+            finger_id,
+            x,
+            y,
+            dx: 0.0,
+            dy: 0.0,
+            pressure: 0.0,
+        })
+    }
+
+    /// Taps at the normalized position `(x, y)` with a finger that is not down.
+    pub fn tap(&mut self, x: f32, y: f32) -> Result<(), String> {
+        let finger_id = (0..).find(|id| !self.fingers.contains_key(id)).unwrap();
+        self.finger_down(finger_id, x, y)?;
+        self.finger_up(finger_id)
+    }
+}
+
+/// The key typing `c` on a US layout, and whether shift must be held.
+fn us_key(c: char) -> Option<(Keycode, bool)> {
+    let (unshifted, shifted) = match c {
+        '\n' => return Some((Keycode::RETURN, false)),
+        '\t' => return Some((Keycode::TAB, false)),
+        'A'..='Z' => (c.to_ascii_lowercase(), true),
+        '~' => ('`', true),
+        '!' => ('1', true),
+        '@' => ('2', true),
+        '#' => ('3', true),
+        '$' => ('4', true),
+        '%' => ('5', true),
+        '^' => ('6', true),
+        '&' => ('7', true),
+        '*' => ('8', true),
+        '(' => ('9', true),
+        ')' => ('0', true),
+        '_' => ('-', true),
+        '+' => ('=', true),
+        '{' => ('[', true),
+        '}' => (']', true),
+        '|' => ('\\', true),
+        ':' => (';', true),
+        '"' => ('\'', true),
+        '<' => (',', true),
+        '>' => ('.', true),
+        '?' => ('/', true),
+        ' '
+        | '0'..='9'
+        | 'a'..='z'
+        | '`'
+        | '-'
+        | '='
+        | '['
+        | ']'
+        | '\\'
+        | ';'
+        | '\''
+        | ','
+        | '.'
+        | '/' => (c, false),
+        _ => return None,
+    };
+    Keycode::from_i32(unshifted as i32).map(|keycode| (keycode, shifted))
+}
+
+fn button_mask(button: MouseButton) -> u32 {
+    match button {
+        MouseButton::Unknown => 0,
+        button => 1 << (button as u32 - 1),
+    }
+}
@@ -0,0 +1,446 @@
+//! Dollar gesture recording and recognition, and multi-finger gesture tracking.
+//!
+//! Dollar gestures are single finger paths (e.g. a circle or a check mark) that SDL recognizes
+//! once a template has been recorded or loaded for them: completed paths are reported as
+//! [`Event::DollarGesture`](../../event/enum.Event.html#variant.DollarGesture) with the ID of
+//! the closest template. A [`GestureBank`] gives names to these IDs and persists them with
+//! their templates.
+
+use std::io::{Read, Write};
+
+use crate::event::Event;
+use crate::get_error;
+use crate::rwops::RWops;
+use crate::sys;
+
+use super::TouchDevice;
+
+pub type GestureId = sys::SDL_GestureID;
+
+/// The size of a template as written by SDL: 64 points of two `f32` coordinates.
+const TEMPLATE_SIZE: usize = 64 * 2 * 4;
+
+const GESTURE_BANK_MAGIC: &[u8; 8] = b"SDLGBANK";
+const GESTURE_BANK_VERSION: u32 = 2;
+
+fn touch_id(touch: Option<TouchDevice>) -> TouchDevice {
+    touch.unwrap_or(-1)
+}
+
+/// Begins recording a gesture on `touch`, or on every touch device if `None`.
+///
+/// The next gesture performed is added as a template, and reported with its ID as an
+/// [`Event::DollarRecord`](../../event/enum.Event.html#variant.DollarRecord).
+#[doc(alias = "SDL_RecordGesture")]
+pub fn record_gesture(touch: Option<TouchDevice>) -> Result<(), String> {
+    if unsafe { sys::SDL_RecordGesture(touch_id(touch)) } == 1 {
+        Ok(())
+    } else {
+        Err("Touch device not found".to_owned())
+    }
+}
+
+/// Writes the template of gesture `id` to `dst`.
+#[doc(alias = "SDL_SaveDollarTemplate")]
+pub fn save_template(id: GestureId, dst: &mut RWops) -> Result<(), String> {
+    crate::clear_error();
+    if unsafe { sys::SDL_SaveDollarTemplate(id, dst.raw()) } > 0 {
+        Ok(())
+    } else {
+        let error = get_error();
+        if error.is_empty() {
+            Err(format!("Unknown gesture ID {}", id))
+        } else {
+            Err(error)
+        }
+    }
+}
+
+/// Writes the templates of every recorded or loaded gesture to `dst`, returning the number of
+/// saved templates.
+#[doc(alias = "SDL_SaveAllDollarTemplates")]
+pub fn save_all_templates(dst: &mut RWops) -> Result<usize, String> {
+    crate::clear_error();
+    let result = unsafe { sys::SDL_SaveAllDollarTemplates(dst.raw()) };
+    if result > 0 {
+        return Ok(result as usize);
+    }
+    // 0 is returned both on failure and when there is nothing to save.
+    let error = get_error();
+    if error.is_empty() {
+        Ok(0)
+    } else {
+        Err(error)
+    }
+}
+
+/// Loads the templates written by [`save_template`] or [`save_all_templates`] from `src` into
+/// `touch`, or into every touch device if `None`, returning the number of loaded templates.
+#[doc(alias = "SDL_LoadDollarTemplates")]
+pub fn load_templates(touch: Option<TouchDevice>, src: &mut RWops) -> Result<usize, String> {
+    crate::clear_error();
+    let result = unsafe { sys::SDL_LoadDollarTemplates(touch_id(touch), src.raw()) };
+    if result > 0 {
+        return Ok(result as usize);
+    }
+    // SDL does not set an error when there is nothing to load the templates into, or nothing
+    // to load.
+    let error = get_error();
+    if !error.is_empty() {
+        Err(error)
+    } else if touch.is_none() && super::num_touch_devices() == 0 {
+        Err("No touch device to load the templates into".to_owned())
+    } else {
+        Err("No gesture template could be read".to_owned())
+    }
+}
+
+/// A gesture of a bank file: its ID, name and template.
+type BankEntry = (GestureId, String, [u8; TEMPLATE_SIZE]);
+
+fn write_bank(entries: &[BankEntry]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(GESTURE_BANK_MAGIC);
+    data.extend_from_slice(&GESTURE_BANK_VERSION.to_le_bytes());
+    data.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (id, name, template) in entries {
+        data.extend_from_slice(&id.to_le_bytes());
+        data.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        data.extend_from_slice(name.as_bytes());
+        data.extend_from_slice(template);
+    }
+    data
+}
+
+fn read_bank<R: Read>(src: &mut R) -> Result<Vec<BankEntry>, String> {
+    fn read_u32<R: Read>(src: &mut R) -> Result<u32, String> {
+        let mut buf = [0; 4];
+        src.read_exact(&mut buf).map_err(|e| e.to_string())?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    let mut magic = [0; 8];
+    src.read_exact(&mut magic).map_err(|e| e.to_string())?;
+    if &magic != GESTURE_BANK_MAGIC {
+        return Err("Not a gesture bank".to_owned());
+    }
+    let version = read_u32(src)?;
+    if version != GESTURE_BANK_VERSION {
+        return Err(format!("Unsupported gesture bank version {}", version));
+    }
+
+    let mut entries = Vec::new();
+    for _ in 0..read_u32(src)? {
+        let mut id = [0; 8];
+        src.read_exact(&mut id).map_err(|e| e.to_string())?;
+        let mut name = Vec::new();
+        let name_len = read_u32(src)? as u64;
+        src.take(name_len)
+            .read_to_end(&mut name)
+            .map_err(|e| e.to_string())?;
+        if name.len() as u64 != name_len {
+            return Err("Truncated gesture bank".to_owned());
+        }
+        let name = String::from_utf8(name).map_err(|e| e.to_string())?;
+        let mut template = [0; TEMPLATE_SIZE];
+        src.read_exact(&mut template).map_err(|e| e.to_string())?;
+        entries.push((GestureId::from_le_bytes(id), name, template));
+    }
+    Ok(entries)
+}
+
+/// Named gesture templates.
+///
+/// Gestures are added by recording them with [`record`](#method.record), or with
+/// [`insert`](#method.insert) for templates recorded by other means. The bank can be saved
+/// to and loaded from an `RWops`, which also loads its templates into SDL.
+///
+/// # Example
+/// ```no_run
+/// use sdl2::event::Event;
+/// use sdl2::rwops::RWops;
+/// use sdl2::touch::gesture::GestureBank;
+///
+/// let sdl = sdl2::init().unwrap();
+/// let mut event_pump = sdl.event_pump().unwrap();
+///
+/// let mut bank = match RWops::from_file("gestures.bin", "rb") {
+///     Ok(mut file) => GestureBank::load(None, &mut file).unwrap(),
+///     Err(_) => GestureBank::new(),
+/// };
+/// if bank.id("circle").is_none() {
+///     bank.record(None, "circle").unwrap();
+/// }
+///
+/// for event in event_pump.wait_iter() {
+///     bank.handle_event(&event);
+///     match event {
+///         Event::DollarRecord { .. } => {
+///             let mut file = RWops::from_file("gestures.bin", "wb").unwrap();
+///             bank.save(&mut file).unwrap();
+///         }
+///         Event::DollarGesture { gesture_id, .. } => {
+///             println!("{:?}", bank.name(gesture_id));
+///         }
+///         Event::Quit { .. } => break,
+///         _ => (),
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GestureBank {
+    gestures: Vec<(GestureId, String)>,
+    /// The name of the gesture being recorded.
+    recording: Option<String>,
+}
+
+impl GestureBank {
+    pub fn new() -> GestureBank {
+        GestureBank {
+            gestures: Vec::new(),
+            recording: None,
+        }
+    }
+
+    /// Begins recording a gesture on `touch`, or on every touch device if `None`.
+    ///
+    /// Once recorded, the gesture is added to the bank as `name` when its
+    /// [`Event::DollarRecord`](../../event/enum.Event.html#variant.DollarRecord) is passed to
+    /// [`handle_event`](#method.handle_event).
+    pub fn record(&mut self, touch: Option<TouchDevice>, name: &str) -> Result<(), String> {
+        record_gesture(touch)?;
+        self.recording = Some(name.to_owned());
+        Ok(())
+    }
+
+    /// Returns the name of the gesture being recorded, if any.
+    pub fn recording(&self) -> Option<&str> {
+        self.recording.as_deref()
+    }
+
+    /// Adds the gesture being recorded to the bank when `event` is its `DollarRecord` event.
+    /// Other events are ignored.
+    pub fn handle_event(&mut self, event: &Event) {
+        if let Event::DollarRecord { gesture_id, .. } = *event {
+            if let Some(name) = self.recording.take() {
+                self.insert(gesture_id, &name);
+            }
+        }
+    }
+
+    /// Names the gesture `id`, replacing any gesture with the same name.
+    pub fn insert(&mut self, id: GestureId, name: &str) {
+        self.gestures
+            .retain(|&(other_id, ref other_name)| other_id != id && other_name != name);
+        self.gestures.push((id, name.to_owned()));
+    }
+
+    /// Removes the gesture named `name`, returning its ID.
+    ///
+    /// Its template stays loaded in SDL, so it can still be recognized.
+    pub fn remove(&mut self, name: &str) -> Option<GestureId> {
+        let index = self.gestures.iter().position(|(_, n)| n == name)?;
+        Some(self.gestures.remove(index).0)
+    }
+
+    /// Returns the name of the gesture `id`.
+    pub fn name(&self, id: GestureId) -> Option<&str> {
+        self.gestures
+            .iter()
+            .find(|&&(other_id, _)| other_id == id)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Returns the ID of the gesture named `name`.
+    pub fn id(&self, name: &str) -> Option<GestureId> {
+        self.gestures
+            .iter()
+            .find(|(_, other_name)| other_name == name)
+            .map(|&(id, _)| id)
+    }
+
+    /// Returns the gestures of the bank, in the order they were added.
+    pub fn gestures(&self) -> impl Iterator<Item = (GestureId, &str)> {
+        self.gestures.iter().map(|(id, name)| (*id, name.as_str()))
+    }
+
+    /// Writes the names, IDs and templates of the gestures of the bank to `dst`.
+    pub fn save(&self, dst: &mut RWops) -> Result<(), String> {
+        let mut entries = Vec::with_capacity(self.gestures.len());
+        for (id, name) in &self.gestures {
+            let mut template = [0; TEMPLATE_SIZE];
+            save_template(*id, &mut RWops::from_bytes_mut(&mut template)?)?;
+            entries.push((*id, name.clone(), template));
+        }
+
+        dst.write_all(&write_bank(&entries))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Reads a bank written by [`save`](#method.save) from `src`, and loads its templates into
+    /// `touch`, or into every touch device if `None`.
+    ///
+    /// Returns an error if SDL does not give a loaded template the ID it was saved with,
+    /// which happens with banks saved on a platform where SDL computes IDs differently.
+    pub fn load(touch: Option<TouchDevice>, src: &mut RWops) -> Result<GestureBank, String> {
+        let mut bank = GestureBank::new();
+        for (id, name, template) in read_bank(src)? {
+            load_templates(touch, &mut RWops::from_bytes(&template)?)?;
+            // SDL looks templates up by their ID, which it computes from their points.
+            let mut saved = [0; TEMPLATE_SIZE];
+            save_template(id, &mut RWops::from_bytes_mut(&mut saved)?)
+                .map_err(|_| format!("Gesture '{}' was loaded by SDL with another ID", name))?;
+            bank.insert(id, &name);
+        }
+        Ok(bank)
+    }
+}
+
+/// Accumulates the rotation and pinch of the multi-finger gestures of a touch device.
+///
+/// # Example
+/// ```no_run
+/// use sdl2::touch::gesture::MultiGestureTracker;
+///
+/// let sdl = sdl2::init().unwrap();
+/// let mut event_pump = sdl.event_pump().unwrap();
+///
+/// let mut zoom = 1.0;
+/// let mut tracker = MultiGestureTracker::new();
+/// for event in event_pump.wait_iter() {
+///     if tracker.handle_event(&event) {
+///         zoom = (1.0 + tracker.pinch()).max(0.1);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MultiGestureTracker {
+    touch_id: Option<TouchDevice>,
+    rotation: f32,
+    pinch: f32,
+    x: f32,
+    y: f32,
+    num_fingers: u16,
+}
+
+impl MultiGestureTracker {
+    /// Creates a tracker for the gestures of every touch device.
+    pub fn new() -> MultiGestureTracker {
+        MultiGestureTracker::default()
+    }
+
+    /// Creates a tracker for the gestures of `touch` only.
+    pub fn for_device(touch: TouchDevice) -> MultiGestureTracker {
+        MultiGestureTracker {
+            touch_id: Some(touch),
+            ..MultiGestureTracker::default()
+        }
+    }
+
+    /// Accumulates `event` if it is a multi-finger gesture event of the tracked device.
+    /// Returns `true` if the event was accumulated.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::MultiGesture {
+                touch_id,
+                d_theta,
+                d_dist,
+                x,
+                y,
+                num_fingers,
+                ..
+            } if self.touch_id.is_none() || self.touch_id == Some(touch_id) => {
+                self.rotation += d_theta;
+                self.pinch += d_dist;
+                self.x = x;
+                self.y = y;
+                self.num_fingers = num_fingers;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The total rotation since the last reset, in radians.
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// The total change of the distance between the fingers since the last reset, in
+    /// normalized touch coordinates. Positive when the fingers move apart.
+    pub fn pinch(&self) -> f32 {
+        self.pinch
+    }
+
+    /// The normalized center of the fingers of the last gesture event.
+    pub fn center(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    /// The number of fingers of the last gesture event.
+    pub fn num_fingers(&self) -> u16 {
+        self.num_fingers
+    }
+
+    /// Resets the accumulated rotation and pinch.
+    pub fn reset(&mut self) {
+        self.rotation = 0.0;
+        self.pinch = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_bank, write_bank, MultiGestureTracker, TEMPLATE_SIZE};
+    use crate::event::Event;
+
+    #[test]
+    fn test_bank_round_trip() {
+        let mut circle = [0; TEMPLATE_SIZE];
+        circle[..4].copy_from_slice(&0.5f32.to_le_bytes());
+        let entries = vec![
+            (-7, "circle".to_owned(), circle),
+            (
+                0x1234_5678_9abc,
+                "check mark ✓".to_owned(),
+                [0xff; TEMPLATE_SIZE],
+            ),
+        ];
+
+        let data = write_bank(&entries);
+        assert_eq!(read_bank(&mut &data[..]).unwrap(), entries);
+
+        assert!(read_bank(&mut &data[..data.len() - 1]).is_err());
+        let mut other_version = data.clone();
+        other_version[8] = 1;
+        assert!(read_bank(&mut &other_version[..]).is_err());
+        assert!(read_bank(&mut &b"SDLGBANX"[..]).is_err());
+    }
+
+    #[test]
+    fn test_multi_gesture_tracker() {
+        let gesture = |touch_id, d_theta, d_dist| Event::MultiGesture {
+            timestamp: 0,
+            touch_id,
+            d_theta,
+            d_dist,
+            x: 0.25,
+            y: 0.75,
+            num_fingers: 2,
+        };
+
+        let mut tracker = MultiGestureTracker::for_device(1);
+        assert!(tracker.handle_event(&gesture(1, 0.5, 0.125)));
+        assert!(tracker.handle_event(&gesture(1, 0.25, -0.25)));
+        assert!(!tracker.handle_event(&gesture(2, 1.0, 1.0)));
+        assert!(!tracker.handle_event(&Event::Quit { timestamp: 0 }));
+        assert_eq!(tracker.rotation(), 0.75);
+        assert_eq!(tracker.pinch(), -0.125);
+        assert_eq!(tracker.center(), (0.25, 0.75));
+        assert_eq!(tracker.num_fingers(), 2);
+
+        tracker.reset();
+        assert_eq!(tracker.rotation(), 0.0);
+        assert_eq!(tracker.pinch(), 0.0);
+    }
+}
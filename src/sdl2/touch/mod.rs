@@ -1,5 +1,7 @@
 use crate::sys;
 
+pub mod gesture;
+
 pub type Finger = sys::SDL_Finger;
 pub type TouchDevice = sys::SDL_TouchID;
 
@@ -10,4 +10,189 @@ fn audio_spec_wav() {
 
     let buffer = wav.buffer();
     assert_eq!(buffer.len(), 4_410);
+
+    // The file is little-endian, which only matches `i16` on little-endian hosts.
+    if wav.format == sdl2::audio::AudioFormat::s16_sys() {
+        assert_eq!(wav.samples::<i16>().unwrap().len(), 2_205);
+    }
+    assert!(wav.samples::<f32>().is_none());
+}
+
+// Only one `Sdl` context can ever be created per test binary, so every test that needs an audio
+// device runs from here. The dummy driver needs no audio hardware.
+#[test]
+fn audio_devices() {
+    std::env::set_var("SDL_AUDIODRIVER", "dummy");
+    let sdl_context = sdl2::init().unwrap();
+    let audio_subsystem = sdl_context.audio().unwrap();
+
+    device_list(&audio_subsystem);
+    capture_queue(&audio_subsystem);
+    dynamic_playback(&audio_subsystem);
+    hotplug_manager(&audio_subsystem);
+}
+
+fn device_list(audio_subsystem: &sdl2::AudioSubsystem) {
+    let playback: Vec<_> = audio_subsystem.playback_devices().collect();
+    assert_eq!(
+        playback.len() as u32,
+        audio_subsystem.num_audio_playback_devices().unwrap_or(0)
+    );
+    for (index, info) in playback.iter().enumerate() {
+        assert!(!info.is_capture);
+        assert_eq!(
+            info.name,
+            audio_subsystem
+                .audio_playback_device_name(index as u32)
+                .unwrap()
+        );
+    }
+    assert!(audio_subsystem
+        .capture_devices()
+        .all(|info| info.is_capture));
+
+    // Not every driver can report a default device; the dummy one cannot.
+    if let Ok(info) = audio_subsystem.default_playback_device() {
+        assert!(!info.is_capture);
+    }
+}
+
+fn capture_queue(audio_subsystem: &sdl2::AudioSubsystem) {
+    use std::time::{Duration, Instant};
+
+    // The dummy driver records silence.
+    let desired_spec = sdl2::audio::AudioSpecDesired {
+        freq: Some(22_050),
+        channels: Some(1),
+        samples: None,
+    };
+    let queue = audio_subsystem
+        .open_capture_queue::<i16, _>(None, &desired_spec)
+        .unwrap();
+    assert_eq!(queue.queued_size(), 0);
+    queue.resume();
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while queue.queued_size() == 0 && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    queue.pause();
+
+    let queued = queue.queued_size();
+    assert!(queued > 0);
+    assert_eq!(queued * 2, queue.size() as usize);
+
+    let mut buffer = vec![1i16; queued + 64];
+    let read = queue.dequeue(&mut buffer);
+    assert_eq!(read, queued);
+    assert!(buffer[..read].iter().all(|&sample| sample == 0));
+    assert_eq!(queue.queued_size(), 0);
+}
+
+fn dynamic_playback(audio_subsystem: &sdl2::AudioSubsystem) {
+    use sdl2::audio::{
+        AllowedChanges, AudioBufferMut, AudioFormat, AudioFormatNum, DynamicAudioCallback,
+    };
+
+    fn fill<T: AudioFormatNum>(out: &mut [T]) {
+        out.iter_mut().for_each(|s| *s = T::SILENCE);
+    }
+
+    /// Plays silence in whatever format the device was opened with.
+    struct Silence(u8);
+
+    impl DynamicAudioCallback for Silence {
+        fn callback(&mut self, out: AudioBufferMut) {
+            match out {
+                AudioBufferMut::U8(out) => fill(out),
+                AudioBufferMut::S8(out) => fill(out),
+                AudioBufferMut::U16(out) => fill(out),
+                AudioBufferMut::S16(out) => fill(out),
+                AudioBufferMut::S32(out) => fill(out),
+                AudioBufferMut::F32(out) => fill(out),
+                AudioBufferMut::Foreign(_, out) => out.iter_mut().for_each(|s| *s = self.0),
+            }
+        }
+    }
+
+    let desired_spec = sdl2::audio::AudioSpecDesired {
+        freq: Some(48_000),
+        channels: Some(2),
+        samples: None,
+    };
+    let mut device = audio_subsystem
+        .open_playback_dynamic(
+            None,
+            &desired_spec,
+            AudioFormat::f32_sys(),
+            AllowedChanges::ANY,
+            |spec| {
+                assert!(
+                    spec.format == AudioFormat::f32_sys() || spec.format == AudioFormat::s16_sys()
+                );
+                Silence(spec.silence)
+            },
+        )
+        .unwrap();
+    let format = device.spec().format;
+    assert_eq!(device.lock().format(), format);
+    device.resume();
+}
+
+fn hotplug_manager(audio_subsystem: &sdl2::AudioSubsystem) {
+    use sdl2::audio::{AudioCallback, AudioDeviceChange, AudioDeviceManager, AudioStatus};
+    use sdl2::event::Event;
+
+    struct Counter(u32);
+
+    impl AudioCallback for Counter {
+        type Channel = f32;
+
+        fn callback(&mut self, out: &mut [f32]) {
+            out.iter_mut().for_each(|s| *s = 0.0);
+        }
+    }
+
+    let desired_spec = sdl2::audio::AudioSpecDesired {
+        freq: Some(44_100),
+        channels: Some(2),
+        samples: None,
+    };
+    // The dummy driver only has a default device, so the preferred one cannot be opened.
+    let mut manager = AudioDeviceManager::open_playback(
+        audio_subsystem,
+        "Missing device",
+        &desired_spec,
+        Counter(7),
+    )
+    .unwrap();
+    assert!(manager.is_open());
+    assert_eq!(manager.device_name(), None);
+    assert_eq!(manager.preferred_device(), Some("Missing device"));
+    manager.resume();
+    assert_eq!(manager.status(), AudioStatus::Playing);
+    manager.with_callback(|counter| counter.0 += 1);
+
+    let unrelated = Event::AudioDeviceRemoved {
+        timestamp: 0,
+        which: manager.device_id().unwrap() + 1,
+        iscapture: false,
+    };
+    assert_eq!(manager.handle_event(&unrelated), None);
+
+    let removed = Event::AudioDeviceRemoved {
+        timestamp: 0,
+        which: manager.device_id().unwrap(),
+        iscapture: false,
+    };
+    match manager.handle_event(&removed) {
+        Some(AudioDeviceChange::Reopened { device: None, spec }) => {
+            assert_eq!(Some(&spec), manager.spec());
+        }
+        change => panic!("unexpected change: {:?}", change),
+    }
+    // The callback state and the playing status survive the move.
+    assert_eq!(manager.status(), AudioStatus::Playing);
+    assert_eq!(manager.with_callback(|counter| counter.0), 8);
+    assert_eq!(manager.into_callback().0, 8);
 }
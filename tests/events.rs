@@ -31,6 +31,11 @@ fn test_events() {
 
     test3(&ev);
     test4(&ev, &mut ep);
+    test5(&ev, &mut ep);
+    test6(&ev, &mut ep);
+    test7(&ev, &mut ep);
+    test8(&ev, &mut ep);
+    test9(&ev, &mut ep);
 }
 
 fn test1(ev: &sdl2::EventSubsystem) {
@@ -113,10 +118,115 @@ fn test4(ev: &sdl2::EventSubsystem, ep: &mut sdl2::EventPump) {
     ev.push_custom_event(event).unwrap();
 
     let received = ep.poll_event().unwrap();
-    if received.is_user_event() {
-        let e2 = received.as_user_event_type::<SomeEventTypeTest4>().unwrap();
-        assert_eq!(e2.a, 42);
-    }
+    let copy = received.clone();
+    let e2 = received
+        .take_user_event::<SomeEventTypeTest4>()
+        .ok()
+        .unwrap();
+    assert_eq!(e2.payload().a, 42);
+
+    // The payload can only be taken once, even from a copy of the event
+    assert!(copy.take_user_event::<SomeEventTypeTest4>().is_err());
+}
+
+struct SomeEventTypeTest5 {
+    counter: std::sync::Arc<()>,
+}
+
+fn test5(ev: &sdl2::EventSubsystem, ep: &mut sdl2::EventPump) {
+    ev.register_custom_event::<SomeEventTypeTest5>().unwrap();
+    let counter = std::sync::Arc::new(());
+    ev.push_custom_event(SomeEventTypeTest5 {
+        counter: counter.clone(),
+    })
+    .unwrap();
+    assert_eq!(std::sync::Arc::strong_count(&counter), 2);
+
+    // Taking the payload hands over the pushed value
+    let e = ep.poll_event().unwrap();
+    let e = e.take_user_event::<SomeEventTypeTest5>().ok().unwrap();
+    assert!(std::sync::Arc::ptr_eq(&e.payload().counter, &counter));
+    drop(e);
+    assert_eq!(std::sync::Arc::strong_count(&counter), 1);
+
+    ev.push_custom_event(SomeEventTypeTest5 {
+        counter: counter.clone(),
+    })
+    .unwrap();
+    assert_eq!(std::sync::Arc::strong_count(&counter), 2);
+
+    // Flushing the queue frees the payloads that were never taken
+    ev.flush_custom_event::<SomeEventTypeTest5>();
+    assert_eq!(std::sync::Arc::strong_count(&counter), 1);
+
+    // A received event that will not be taken releases its payload when discarded
+    ev.push_custom_event(SomeEventTypeTest5 {
+        counter: counter.clone(),
+    })
+    .unwrap();
+    let e = ep.poll_event().unwrap();
+    let copy = e.clone();
+    assert_eq!(std::sync::Arc::strong_count(&counter), 2);
+    assert!(e.discard_user_event());
+    assert_eq!(std::sync::Arc::strong_count(&counter), 1);
+    assert!(!copy.discard_user_event());
+
+    // Zero-sized payloads all live at the same address, but are still told apart
+    ev.register_custom_event::<ZeroSizedEventTest5>().unwrap();
+    ev.push_custom_event(ZeroSizedEventTest5).unwrap();
+    ev.push_custom_event(ZeroSizedEventTest5).unwrap();
+    let first = ep.poll_event().unwrap();
+    let second = ep.poll_event().unwrap();
+    assert!(first.take_user_event::<ZeroSizedEventTest5>().is_ok());
+    assert!(second.take_user_event::<ZeroSizedEventTest5>().is_ok());
+}
+
+struct ZeroSizedEventTest5;
+
+struct SomeEventTypeTest6 {
+    a: i32,
+}
+
+struct NoopWaker;
+
+impl std::task::Wake for NoopWaker {
+    fn wake(self: std::sync::Arc<Self>) {}
+}
+
+fn poll_received(
+    received: &mut sdl2::event::CustomEventReceived,
+) -> std::task::Poll<Result<(), String>> {
+    use std::future::Future;
+
+    let waker = std::task::Waker::from(std::sync::Arc::new(NoopWaker));
+    let mut cx = std::task::Context::from_waker(&waker);
+    std::pin::Pin::new(received).poll(&mut cx)
+}
+
+fn test6(ev: &sdl2::EventSubsystem, ep: &mut sdl2::EventPump) {
+    ev.register_custom_event::<SomeEventTypeTest6>().unwrap();
+    let sender = ev.event_sender();
+
+    // Resolves once the event is polled, whether or not its payload is taken
+    let mut received = sender
+        .push_custom_event_received(SomeEventTypeTest6 { a: 42 })
+        .unwrap();
+    assert!(poll_received(&mut received).is_pending());
+
+    let e = ep.poll_event().unwrap();
+    assert_eq!(poll_received(&mut received), std::task::Poll::Ready(Ok(())));
+    let e = e.take_user_event::<SomeEventTypeTest6>().ok().unwrap();
+    assert_eq!(e.payload().a, 42);
+
+    // Fails once the payload is discarded
+    let mut received = sender
+        .push_custom_event_received(SomeEventTypeTest6 { a: 43 })
+        .unwrap();
+    ev.flush_custom_event::<SomeEventTypeTest6>();
+    assert!(matches!(
+        poll_received(&mut received),
+        std::task::Poll::Ready(Err(_))
+    ));
 }
 
 #[test]
@@ -145,3 +255,215 @@ fn test_event_sender_no_subsystem() {
         })
         .is_err());
 }
+
+fn test7(ev: &sdl2::EventSubsystem, ep: &mut sdl2::EventPump) {
+    let shown = event::Event::Window {
+        timestamp: 0,
+        window_id: 0,
+        win_event: event::WindowEvent::Shown,
+    };
+    let hidden = event::Event::Window {
+        timestamp: 0,
+        window_id: 0,
+        win_event: event::WindowEvent::Hidden,
+    };
+
+    let filter = ev.set_event_filter(|event: &event::Event| {
+        !matches!(
+            event,
+            event::Event::Window {
+                win_event: event::WindowEvent::Hidden,
+                ..
+            }
+        )
+    });
+    assert!(filter.is_active());
+    assert!(ev.push_event(shown.clone()).is_ok());
+    assert!(ev.push_event(hidden.clone()).is_err());
+    drop(filter);
+    assert!(ev.push_event(hidden.clone()).is_ok());
+
+    // Purge the `Shown` event that is still queued
+    ev.filter_queued_events(|event: &event::Event| {
+        !matches!(
+            event,
+            event::Event::Window {
+                win_event: event::WindowEvent::Shown,
+                ..
+            }
+        )
+    });
+
+    let mut received = Vec::new();
+    while let Some(event) = ep.poll_event() {
+        if let event::Event::Window { win_event, .. } = event {
+            received.push(win_event);
+        }
+    }
+    assert_eq!(received, vec![event::WindowEvent::Hidden]);
+
+    // Dropping a replaced filter keeps the new one, even when both closures are zero-sized
+    let first = ev.set_event_filter(|_: &event::Event| true);
+    let second = ev.set_event_filter(|_: &event::Event| false);
+    assert!(!first.is_active());
+    drop(first);
+    assert!(second.is_active());
+    assert!(ev.push_event(shown).is_err());
+    drop(second);
+}
+
+fn test8(ev: &sdl2::EventSubsystem, ep: &mut sdl2::EventPump) {
+    use sdl2::keyboard::{Keycode, Mod};
+    use sdl2::mouse::MouseButton;
+    use sdl2::testing::InputInjector;
+
+    let mut input = InputInjector::new(ev);
+
+    input.type_text("aB!").unwrap();
+    let events: Vec<_> = ep.poll_iter().collect();
+    let keys: Vec<_> = events
+        .iter()
+        .filter_map(|event| match *event {
+            event::Event::KeyDown {
+                keycode, keymod, ..
+            } => Some((true, keycode.unwrap(), keymod)),
+            event::Event::KeyUp {
+                keycode, keymod, ..
+            } => Some((false, keycode.unwrap(), keymod)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            (true, Keycode::A, Mod::NOMOD),
+            (false, Keycode::A, Mod::NOMOD),
+            (true, Keycode::LSHIFT, Mod::LSHIFTMOD),
+            (true, Keycode::B, Mod::LSHIFTMOD),
+            (false, Keycode::B, Mod::LSHIFTMOD),
+            (false, Keycode::LSHIFT, Mod::NOMOD),
+            (true, Keycode::LSHIFT, Mod::LSHIFTMOD),
+            (true, Keycode::NUM_1, Mod::LSHIFTMOD),
+            (false, Keycode::NUM_1, Mod::LSHIFTMOD),
+            (false, Keycode::LSHIFT, Mod::NOMOD),
+        ]
+    );
+    let text: String = events
+        .into_iter()
+        .filter_map(|event| match event {
+            event::Event::TextInput { text, .. } => Some(text),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(text, "aB!");
+
+    input.drag(MouseButton::Left, 10, -4, 2).unwrap();
+    let events: Vec<_> = ep.poll_iter().collect();
+    assert_eq!(events.len(), 4);
+    assert!(matches!(
+        events[0],
+        event::Event::MouseButtonDown {
+            mouse_btn: MouseButton::Left,
+            x: 0,
+            y: 0,
+            ..
+        }
+    ));
+    match events[2] {
+        event::Event::MouseMotion {
+            mousestate,
+            x,
+            y,
+            xrel,
+            yrel,
+            ..
+        } => {
+            assert!(mousestate.left());
+            assert_eq!((x, y, xrel, yrel), (10, -4, 5, -2));
+        }
+        ref other => panic!("Unexpected event {:?}", other),
+    }
+    assert!(matches!(
+        events[3],
+        event::Event::MouseButtonUp {
+            mouse_btn: MouseButton::Left,
+            x: 10,
+            y: -4,
+            ..
+        }
+    ));
+
+    input.finger_down(7, 0.5, 0.5).unwrap();
+    input.finger_move(7, 0.75, 0.25).unwrap();
+    assert!(input.finger_move(8, 0.0, 0.0).is_err());
+    input.finger_up(7).unwrap();
+    let events: Vec<_> = ep.poll_iter().collect();
+    assert_eq!(events.len(), 3);
+    assert!(matches!(
+        events[1],
+        event::Event::FingerMotion {
+            finger_id: 7,
+            dx,
+            dy,
+            ..
+        } if dx == 0.25 && dy == -0.25
+    ));
+}
+
+fn test9(ev: &sdl2::EventSubsystem, ep: &mut sdl2::EventPump) {
+    use sdl2::event::{EventType, PeepAction};
+
+    ev.flush_events(0, u32::MAX);
+
+    let window_shown = event::Event::Window {
+        timestamp: 0,
+        window_id: 0,
+        win_event: event::WindowEvent::Shown,
+    };
+    let button_down = event::Event::ControllerButtonDown {
+        timestamp: 0,
+        which: 0,
+        button: sdl2::controller::Button::A,
+    };
+    let device_added = event::Event::ControllerDeviceAdded {
+        timestamp: 0,
+        which: 0,
+    };
+    let added = ev
+        .peep_events(
+            PeepAction::Add(&[window_shown.clone(), button_down.clone(), device_added]),
+            EventType::First..=EventType::Last,
+            2,
+        )
+        .unwrap();
+    assert_eq!(added, [window_shown.clone(), button_down.clone()]);
+
+    let controllers = EventType::ControllerAxisMotion..=EventType::ControllerTouchpadUp;
+    assert!(ev.has_event(EventType::Window));
+    assert!(!ev.has_event(EventType::ControllerDeviceAdded));
+    assert!(ev.has_events(controllers.clone()));
+
+    let peeked = ev
+        .peep_events(PeepAction::Peek, controllers.clone(), 16)
+        .unwrap();
+    assert_eq!(peeked, std::slice::from_ref(&button_down));
+    let taken = ev
+        .peep_events(PeepAction::Get, controllers.clone(), 16)
+        .unwrap();
+    assert_eq!(taken, [button_down]);
+    assert!(!ev.has_events(controllers));
+
+    // Huge amounts are clamped to the capacity of the queue
+    let peeked = ev
+        .peep_events(
+            PeepAction::Peek,
+            EventType::First..=EventType::Last,
+            u32::MAX,
+        )
+        .unwrap();
+    assert_eq!(peeked, std::slice::from_ref(&window_shown));
+
+    // Events outside of the range are left in the queue
+    assert_eq!(ep.poll_event(), Some(window_shown));
+    assert_eq!(ep.poll_event(), None);
+}